@@ -37,6 +37,7 @@ use ripple_sdk::{
 
 use crate::{
     launcher_lifecycle_processor::LauncherLifecycleEventProcessor, launcher_state::LauncherState,
+    manager::app_launcher::AppLauncher,
 };
 
 fn init_library() -> CExtnMetadata {
@@ -70,6 +71,8 @@ fn start_launcher(sender: ExtnSender, receiver: Receiver<CExtnMessage>) {
             let state = LauncherState::new(client.clone())
                 .await
                 .expect("state initialization to succeed");
+            // Re-adopt apps left running by a previous instance of the launcher
+            AppLauncher::recover(&state).await;
             // Create a client for processors
             let mut client_for_processor = client.clone();
 
@@ -64,16 +64,20 @@ impl LauncherState {
             extn_client.clone().request(Config::LauncherConfig).await;
         if let Ok(message) = extn_message_response {
             if let Some(config) = message.payload.clone().extract() {
-                return Ok(LauncherState {
-                    config,
-                    view_state: ViewState::default(),
-                    container_state: ContainerState::default(),
-                    app_launcher_state: AppLauncherState::default(),
-                    extn_client,
-                });
+                return Ok(LauncherState::from_config(config, extn_client));
             }
         }
 
         Err(RippleError::BootstrapError)
     }
+
+    pub fn from_config(config: LauncherConfig, extn_client: ExtnClient) -> LauncherState {
+        LauncherState {
+            view_state: ViewState::default(),
+            container_state: ContainerState::new(&config.saved_dir),
+            app_launcher_state: AppLauncherState::new(&config.saved_dir),
+            config,
+            extn_client,
+        }
+    }
 }
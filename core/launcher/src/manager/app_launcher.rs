@@ -28,8 +28,9 @@ use ripple_sdk::{
             AppRuntimeTransport, AppSession, CloseReason, Dimensions, StateChange,
         },
         device::{
-            device_browser::{BrowserNameRequestParams, BrowserRequest},
+            device_browser::{BrowserDestroyParams, BrowserNameRequestParams, BrowserRequest},
             device_info_request::DeviceInfoRequest,
+            device_window_manager::WindowManagerRequest,
             entertainment_data::{HomeIntent, NavigationIntent},
         },
         firebolt::{
@@ -40,10 +41,14 @@ use ripple_sdk::{
                 SetStateRequest,
             },
         },
-        manifest::{app_library::AppLibrary, apps::AppManifest, device_manifest::RetentionPolicy},
+        manifest::{
+            app_library::AppLibrary,
+            apps::{AppInstances, AppManifest, RelaunchPolicy},
+            device_manifest::RetentionPolicy,
+        },
     },
     extn::extn_client_message::ExtnResponse,
-    framework::RippleResponse,
+    framework::{file_store::FileStore, RippleResponse},
     log::{debug, error, info, warn},
     tokio::{
         self,
@@ -51,6 +56,7 @@ use ripple_sdk::{
     },
     uuid::Uuid,
 };
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::{
@@ -59,7 +65,7 @@ use crate::{
 };
 
 use super::{
-    container_manager::ContainerProperties,
+    container_manager::{ContainerProperties, PersistedContainers},
     container_message::ContainerEvent,
     view_manager::{LaunchParams, ViewManager},
 };
@@ -83,6 +89,9 @@ struct App {
     pub always_retained: bool,
     pub launch_time: u128,
     pub on_destroyed_action: Option<OnDestroyedAction>,
+    /// Zero for the primary instance, which owns the app session in main. Additional
+    /// instances of multi-instance apps share that session.
+    pub instance: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -90,42 +99,111 @@ pub enum OnDestroyedAction {
     Launch(LaunchRequest),
 }
 
+/// Subset of an [App] persisted under the saved directory so a running app can be
+/// re-adopted after a restart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppRecord {
+    pub app_id: String,
+    pub browser_name: String,
+    pub state: LifecycleState,
+    pub initial_intent: NavigationIntent,
+    pub current_intent: NavigationIntent,
+    pub container_props: ContainerProperties,
+    pub launch_time: u128,
+    pub instance: u32,
+}
+
+impl From<&App> for AppRecord {
+    fn from(app: &App) -> Self {
+        AppRecord {
+            app_id: app.app_id.clone(),
+            browser_name: app.launch_params.browser_name.clone(),
+            state: app.state,
+            initial_intent: app.initial_intent.clone(),
+            current_intent: app.current_intent.clone(),
+            container_props: app.container_props.clone(),
+            launch_time: app.launch_time,
+            instance: app.instance,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RecentLaunch {
+    time: u128,
+    intent: Value,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct AppLauncherState {
     apps: Arc<RwLock<HashMap<String, App>>>,
+    recent_launches: Arc<RwLock<HashMap<String, RecentLaunch>>>,
+    store: Option<Arc<RwLock<AppRecordStore>>>,
 }
 
+type AppRecordStore = FileStore<HashMap<String, AppRecord>>;
+
 pub struct AppLauncher;
 
 const OFFLINE_APP_CAP: &'static str = "xrn:firebolt:capability:app:offline";
-fn get_app_type(manifest: &AppManifest) -> Option<String> {
-    match manifest.runtime.as_str() {
-        "web" => Some("HtmlApp".into()),
-        "lightning" => match manifest.requires_capability(OFFLINE_APP_CAP) {
-            true => Some("ResidentApp".into()),
-            false => Some("LightningApp".into()),
-        },
-        _ => None,
-    }
+
+const RECOVER_ATTEMPTS: u32 = 3;
+const RECOVER_RETRY_DELAY_MS: u64 = 1000;
+
+/// Resolves the app runtime against the runtimes supported by the device, returning the
+/// runtime id and whether the app should be loaded as a resident client.
+fn get_runtime(state: &LauncherState, manifest: &AppManifest) -> Option<(String, bool)> {
+    let runtime = state.config.runtime_registry.get(&manifest.runtime)?;
+    let resident = runtime.capabilities.resident && manifest.requires_capability(OFFLINE_APP_CAP);
+    Some((runtime.id, resident))
 }
 
 impl AppLauncherState {
-    fn get_active_instances(self, manifest: &AppManifest) -> usize {
-        match get_app_type(manifest) {
-            Some(t) => self
+    pub fn new(saved_dir: &str) -> AppLauncherState {
+        let path = format!("{}launcher_apps", saved_dir);
+        let store = if let Ok(v) = FileStore::load(path.clone()) {
+            v
+        } else {
+            FileStore::new(path, HashMap::new())
+        };
+        AppLauncherState {
+            store: Some(Arc::new(RwLock::new(store))),
+            ..Default::default()
+        }
+    }
+
+    /// Apps persisted by the previous run, available until the first change is synced.
+    fn get_persisted(&self) -> HashMap<String, AppRecord> {
+        match &self.store {
+            Some(store) => store.read().unwrap().value.clone(),
+            None => HashMap::new(),
+        }
+    }
+
+    fn sync(&self) {
+        if let Some(store) = &self.store {
+            let records = self
                 .apps
                 .read()
                 .unwrap()
                 .iter()
-                .filter(|(_app_id, app)| app.launch_params._type.eq(&t))
-                .count(),
-            None => {
-                println!("get_active_instances: Invalid manifest");
-                0
-            }
+                .map(|(key, app)| (key.clone(), AppRecord::from(app)))
+                .collect();
+            let mut store = store.write().unwrap();
+            store.value = records;
+            store.sync();
         }
     }
 
+    fn get_active_instances(self, manifest: &AppManifest) -> usize {
+        self.apps
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(_app_id, app)| app.launch_params.runtime.eq(&manifest.runtime))
+            .count()
+    }
+
     fn get_app_len(&self) -> usize {
         self.apps.read().unwrap().len()
     }
@@ -147,11 +225,14 @@ impl AppLauncherState {
     }
 
     fn set_app_state(&self, container_id: &str, lifecycle_state: LifecycleState) {
-        let mut v = self.apps.write().unwrap();
-        let r = v.get_mut(container_id);
-        if r.is_some() {
-            r.unwrap().state = lifecycle_state
+        {
+            let mut v = self.apps.write().unwrap();
+            let r = v.get_mut(container_id);
+            if r.is_some() {
+                r.unwrap().state = lifecycle_state
+            }
         }
+        self.sync();
     }
 
     fn set_app_ready(&self, app_id: &str) {
@@ -163,21 +244,87 @@ impl AppLauncherState {
     }
 
     fn set_app_viewid(&self, container_id: &str, view_id: Uuid) {
-        let mut v = self.apps.write().unwrap();
-        let r = v.get_mut(container_id);
-        if r.is_some() {
-            r.unwrap().container_props.view_id = view_id
+        {
+            let mut v = self.apps.write().unwrap();
+            let r = v.get_mut(container_id);
+            if r.is_some() {
+                r.unwrap().container_props.view_id = view_id
+            }
         }
+        self.sync();
     }
 
     fn add_app(&self, key: String, app: App) {
-        let mut r = self.apps.write().unwrap();
-        r.insert(key, app);
+        self.apps.write().unwrap().insert(key, app);
+        self.sync();
     }
 
     fn remove_app(&self, key: &str) -> Option<App> {
-        let mut r = self.apps.write().unwrap();
-        r.remove(key)
+        let app = self.apps.write().unwrap().remove(key);
+        self.sync();
+        app
+    }
+
+    fn set_on_destroyed_action(&self, container_id: &str, action: OnDestroyedAction) {
+        let mut v = self.apps.write().unwrap();
+        if let Some(app) = v.get_mut(container_id) {
+            app.on_destroyed_action = Some(action);
+        }
+    }
+
+    fn get_instances(&self, app_id: &str) -> Vec<App> {
+        let r = self.apps.read().unwrap();
+        r.values()
+            .filter(|app| app.app_id.eq(app_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Finds the instance of the app matching the given state, preferring the container
+    /// keyed by the app id itself.
+    fn find_instance_in_state(&self, app_id: &str, state: LifecycleState) -> Option<App> {
+        if let Some(app) = self.get_app_by_id(app_id) {
+            if app.state == state {
+                return Some(app);
+            }
+        }
+        self.get_instances(app_id)
+            .into_iter()
+            .find(|app| app.state == state)
+    }
+
+    fn next_instance(&self, app_id: &str) -> u32 {
+        self.get_instances(app_id)
+            .iter()
+            .map(|app| app.instance + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Records the launch request and returns true if an identical request for the same
+    /// app was already received within the given window.
+    fn is_duplicate_launch(&self, request: &LaunchRequest, window_ms: u64) -> bool {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis();
+        let intent = serde_json::to_value(&request.intent).unwrap_or_default();
+        let mut recent = self.recent_launches.write().unwrap();
+        if let Some(last) = recent.get(&request.app_id) {
+            if last.intent == intent && now.saturating_sub(last.time) < window_ms as u128 {
+                return true;
+            }
+        }
+        recent.insert(request.app_id.clone(), RecentLaunch { time: now, intent });
+        false
+    }
+
+    /// Forgets the launch request so a retry of a failed launch is not ignored.
+    fn forget_launch(&self, request: &LaunchRequest) {
+        self.recent_launches
+            .write()
+            .unwrap()
+            .remove(&request.app_id);
     }
 
     fn always_retained_apps(&self, policy: RetentionPolicy) -> Vec<App> {
@@ -252,15 +399,20 @@ impl AppLauncher {
                     );
                     let app_id = app.app_id.clone();
 
-                    // Call Set State Request for Extn Sender
-                    if let Err(e) = state
-                        .send_extn_request(LifecycleManagementRequest::SetState(SetStateRequest {
-                            app_id,
-                            state: lc_state.clone(),
-                        }))
-                        .await
-                    {
-                        error!("Error while setting state {:?}", e);
+                    // Call Set State Request for Extn Sender, additional instances share the
+                    // session of the primary instance so they do not drive its state.
+                    if app.instance == 0 {
+                        if let Err(e) = state
+                            .send_extn_request(LifecycleManagementRequest::SetState(
+                                SetStateRequest {
+                                    app_id,
+                                    state: lc_state.clone(),
+                                },
+                            ))
+                            .await
+                        {
+                            error!("Error while setting state {:?}", e);
+                        }
                     }
 
                     let state_change = StateChangeInternal {
@@ -464,31 +616,28 @@ impl AppLauncher {
     pub async fn on_container_event(state: &LauncherState, event: ContainerEvent) {
         match event {
             ContainerEvent::Focused(previous, next) => {
-                if let Some(p) = &previous {
+                let unchanged = match (&previous, &next) {
+                    (Some(p), Some(n)) => p.view_id.eq(&n.view_id),
+                    _ => false,
+                };
+
+                if !unchanged {
                     if let Some(n) = &next {
-                        if p.view_id.eq(&n.view_id) {
-                            return;
-                        }
+                        Self::set_state(state.clone(), n.name.clone(), LifecycleState::Foreground)
+                            .await
+                            .ok();
                     }
                 }
 
-                if let Some(n) = &next {
-                    Self::set_state(state.clone(), n.name.clone(), LifecycleState::Foreground)
-                        .await
-                        .ok();
-                }
-
-                if let Some(p) = &previous {
-                    if let Some(previous_app) = state.app_launcher_state.get_app_by_id(&p.name) {
-                        if previous_app.state == LifecycleState::Foreground {
-                            Self::set_state(
-                                state.clone(),
-                                p.name.clone(),
-                                LifecycleState::Background,
-                            )
+                Self::background_covered_apps(state, next.map(|n| n.name)).await;
+            }
+            ContainerEvent::Added(props) if !props.layer.takes_focus() => {
+                // Picture-in-picture containers are visible without ever taking focus
+                if let Some(app) = state.app_launcher_state.get_app_by_id(&props.name) {
+                    if app.state == LifecycleState::Inactive {
+                        Self::set_state(state.clone(), props.name, LifecycleState::Background)
                             .await
                             .ok();
-                        }
                     }
                 }
             }
@@ -515,6 +664,24 @@ impl AppLauncher {
         }
     }
 
+    /// Moves foreground apps to the background unless they hold focus or are at the top of
+    /// the main layer, which stays in the foreground while an overlay is focused.
+    async fn background_covered_apps(state: &LauncherState, focused: Option<String>) {
+        let top_main = ContainerManager::get_top_main_container(state).map(|p| p.name);
+        for app in state.app_launcher_state.get_apps() {
+            let name = app.container_props.name;
+            if app.state != LifecycleState::Foreground
+                || Some(&name) == focused.as_ref()
+                || Some(&name) == top_main.as_ref()
+            {
+                continue;
+            }
+            Self::set_state(state.clone(), name, LifecycleState::Background)
+                .await
+                .ok();
+        }
+    }
+
     fn get_transport(url: String) -> AppRuntimeTransport {
         if url.as_str().find("__firebolt_endpoint").is_none() {
             AppRuntimeTransport::Bridge
@@ -547,14 +714,22 @@ impl AppLauncher {
             },
         };
 
-        if let Err(e) = state
+        match state
             .send_extn_request(LifecycleManagementRequest::Session(AppSessionRequest {
                 session,
             }))
             .await
         {
-            error!("Error while prelaunching {:?}", e);
-            return Err(e);
+            Ok(msg) => {
+                if let Some(ExtnResponse::Error(e)) = msg.payload.extract() {
+                    error!("Session refused while prelaunching {:?}", e);
+                    return Err(e);
+                }
+            }
+            Err(e) => {
+                error!("Error while prelaunching {:?}", e);
+                return Err(e);
+            }
         }
         Ok(())
     }
@@ -562,6 +737,28 @@ impl AppLauncher {
     pub async fn launch(
         state: &LauncherState,
         request: LaunchRequest,
+    ) -> Result<AppManagerResponse, AppError> {
+        let window_ms = state.config.lifecycle_policy.launch_dedup_window_ms;
+        if state
+            .app_launcher_state
+            .is_duplicate_launch(&request, window_ms)
+        {
+            info!(
+                "launch: app_id={} Ignoring repeated launch request",
+                request.app_id
+            );
+            return Ok(AppManagerResponse::None);
+        }
+        let result = Self::launch_app(state, request.clone()).await;
+        if result.is_err() {
+            state.app_launcher_state.forget_launch(&request);
+        }
+        result
+    }
+
+    async fn launch_app(
+        state: &LauncherState,
+        request: LaunchRequest,
     ) -> Result<AppManagerResponse, AppError> {
         let resp = AppLibrary::get_manifest(&state.config.app_library_state, &request.app_id);
         if let None = resp {
@@ -570,9 +767,25 @@ impl AppLauncher {
 
         let app_manifest = resp.unwrap();
 
-        let app_type = get_app_type(&app_manifest);
-        if let None = app_type {
-            return Err(AppError::NotSupported);
+        let (runtime, resident) = match get_runtime(state, &app_manifest) {
+            Some(runtime) => runtime,
+            None => return Err(AppError::NotSupported),
+        };
+
+        let mut container_key = request.app_id.clone();
+        let mut instance = 0;
+        if let Some(existing) = state.app_launcher_state.get_app_by_id(&request.app_id) {
+            match app_manifest.instances {
+                AppInstances::Multi => {
+                    instance = state.app_launcher_state.next_instance(&request.app_id);
+                    container_key = format!("{}-{}", request.app_id, instance);
+                }
+                AppInstances::Single => {
+                    if let RelaunchPolicy::Restart = app_manifest.relaunch {
+                        return Self::restart(state, existing, request).await;
+                    }
+                }
+            }
         }
         let instances = state
             .clone()
@@ -618,8 +831,12 @@ impl AppLauncher {
         let launch_params = LaunchParams {
             uri: app_manifest.start_page.to_string(),
             browser_name: callsign,
-            _type: app_type.unwrap(),
-            name: app_manifest.name.to_string(),
+            runtime,
+            resident,
+            name: match instance {
+                0 => app_manifest.name.to_string(),
+                _ => container_key.clone(),
+            },
             suspend: false,
             requires_focus: true,
             x: app_manifest.x,
@@ -655,6 +872,7 @@ impl AppLauncher {
                 view_id: Uuid::nil(),
                 requires_focus: launch_params.requires_focus,
                 dimensions: dims,
+                layer: app_manifest.layer,
             },
             session_id: "none".into(),
             always_retained: always_retained.is_some(),
@@ -663,9 +881,10 @@ impl AppLauncher {
                 .unwrap()
                 .as_millis(),
             on_destroyed_action: None,
+            instance,
         };
 
-        let existing_app = state.app_launcher_state.get_app_by_id(&request.app_id);
+        let existing_app = state.app_launcher_state.get_app_by_id(&container_key);
         if let Some(existing) = existing_app {
             app.initial_intent = existing.initial_intent.clone();
             app.state = existing.state;
@@ -673,9 +892,7 @@ impl AppLauncher {
             app.session_id = existing.session_id.clone();
             let container_props = app.container_props.clone();
             // Update manifest map.
-            state
-                .app_launcher_state
-                .add_app(request.app_id.clone(), app);
+            state.app_launcher_state.add_app(container_key, app);
             match ContainerManager::add(&state, container_props).await {
                 Ok(_) => return Ok(AppManagerResponse::None),
                 Err(_) => return Err(AppError::IoError),
@@ -683,47 +900,234 @@ impl AppLauncher {
         } else {
             let timeout = state.clone().config.lifecycle_policy.app_ready_timeout_ms;
             let state_c = state.clone();
-            let app_id = request.app_id.clone();
+            let key = container_key.clone();
             let launch_time = app.launch_time;
             tokio::spawn(async move {
                 sleep(Duration::from_millis(timeout)).await;
-                let _ = Self::check_ready(&state_c, &app_id, launch_time).await;
+                let _ = Self::check_ready(&state_c, &key, launch_time).await;
             });
         }
 
-        state
-            .app_launcher_state
-            .add_app(request.app_id.clone(), app);
+        state.app_launcher_state.add_app(container_key.clone(), app);
         // TODO move logic for permission store to Delegated app launcher
 
         match ViewManager::acquire_view(&state, launch_params.clone()).await {
             Ok(view_id) => {
                 state
                     .app_launcher_state
-                    .set_app_viewid(&request.app_id, view_id);
+                    .set_app_viewid(&container_key, view_id);
+                if instance > 0 {
+                    // Additional instances share an already initialized session so main will
+                    // not report them ready.
+                    state.app_launcher_state.set_app_ready(&container_key);
+                    return Self::set_state(state.clone(), container_key, LifecycleState::Inactive)
+                        .await;
+                }
                 Ok(AppManagerResponse::None)
             }
             Err(e) => {
                 error!("view acquire failed {:?}", e);
-                state.app_launcher_state.remove_app(&request.app_id);
+                state.app_launcher_state.remove_app(&container_key);
                 Err(AppError::General)
             }
         }
     }
 
+    async fn restart(
+        state: &LauncherState,
+        existing: App,
+        request: LaunchRequest,
+    ) -> Result<AppManagerResponse, AppError> {
+        let key = existing.container_props.name.clone();
+        debug!("restart: app_id={} container={}", request.app_id, key);
+        state
+            .app_launcher_state
+            .set_on_destroyed_action(&key, OnDestroyedAction::Launch(request));
+        Self::set_state(state.clone(), key, LifecycleState::Unloading).await
+    }
+
+    /// Lists the clients loaded in the window manager, retrying while the window manager is
+    /// not answering.
+    async fn get_clients(state: &LauncherState) -> Option<Vec<String>> {
+        for attempt in 1..=RECOVER_ATTEMPTS {
+            match state
+                .send_extn_request(WindowManagerRequest::GetClients)
+                .await
+            {
+                Ok(msg) => match msg.payload.extract() {
+                    Some(ExtnResponse::List(clients)) => return Some(clients),
+                    _ => error!("recover: Unexpected window manager clients response"),
+                },
+                Err(e) => error!("recover: Could not get window manager clients {:?}", e),
+            }
+            if attempt < RECOVER_ATTEMPTS {
+                sleep(Duration::from_millis(RECOVER_RETRY_DELAY_MS)).await;
+            }
+        }
+        None
+    }
+
+    /// Reconciles the apps persisted by the previous run against the clients loaded in the
+    /// window manager. Running apps are re-adopted and records without a client are dropped.
+    /// Clients of apps which can no longer be launched are destroyed, other adoption failures
+    /// are left running. Clients Ripple did not persist are never touched, and nothing is
+    /// touched when the clients can't be listed, the persisted apps are kept for the next start.
+    pub async fn recover(state: &LauncherState) {
+        let records = state.app_launcher_state.get_persisted();
+        let persisted_containers = state.container_state.get_persisted();
+        if records.is_empty() {
+            return;
+        }
+
+        let clients = match Self::get_clients(state).await {
+            Some(clients) => clients,
+            None => {
+                error!("recover: Window manager unavailable, keeping persisted apps");
+                return;
+            }
+        };
+
+        let mut adopted = Vec::new();
+        for (key, record) in records {
+            if !clients.contains(&record.browser_name.to_lowercase()) {
+                info!("recover: app_id={} No longer loaded, dropping", key);
+                continue;
+            }
+            match Self::adopt(state, key.clone(), record.clone()).await {
+                Ok(_) => {
+                    info!("recover: app_id={} Re-adopted", key);
+                    adopted.push(record.container_props.name);
+                }
+                Err(e @ (AppError::NotFound | AppError::NotSupported)) => {
+                    warn!("recover: app_id={} Destroying orphan {:?}", key, e);
+                    if let Err(e) = state
+                        .send_extn_request(BrowserRequest::Destroy(BrowserDestroyParams {
+                            browser_name: record.browser_name,
+                        }))
+                        .await
+                    {
+                        error!("recover: Error destroying orphan {:?}", e);
+                    }
+                }
+                Err(e) => warn!(
+                    "recover: app_id={} Could not re-adopt, leaving it running {:?}",
+                    key, e
+                ),
+            }
+        }
+
+        let mut stack = persisted_containers.stack;
+        for name in stack.items() {
+            if !adopted.contains(&name) {
+                stack.pop_item(&name);
+            }
+        }
+        let containers = persisted_containers
+            .containers
+            .into_iter()
+            .filter(|(name, _)| adopted.contains(name))
+            .collect();
+        let top = stack.peek().cloned();
+        state
+            .container_state
+            .restore(PersistedContainers { stack, containers });
+        if let Some(top) = top {
+            ContainerManager::bring_to_front(state, &top).await.ok();
+        }
+    }
+
+    async fn adopt(
+        state: &LauncherState,
+        key: String,
+        record: AppRecord,
+    ) -> Result<AppManagerResponse, AppError> {
+        let app_manifest =
+            match AppLibrary::get_manifest(&state.config.app_library_state, &record.app_id) {
+                Some(manifest) => manifest,
+                None => return Err(AppError::NotFound),
+            };
+        let (runtime, resident) = match get_runtime(state, &app_manifest) {
+            Some(runtime) => runtime,
+            None => return Err(AppError::NotSupported),
+        };
+
+        if record.instance == 0 {
+            Self::pre_launch(
+                state,
+                app_manifest.clone(),
+                record.browser_name.clone(),
+                record.current_intent.clone(),
+            )
+            .await
+            .map_err(|_| AppError::IoError)?;
+            if record.state != LifecycleState::Initializing {
+                if let Err(e) = state
+                    .send_extn_request(LifecycleManagementRequest::SetState(SetStateRequest {
+                        app_id: record.app_id.clone(),
+                        state: record.state,
+                    }))
+                    .await
+                {
+                    error!("adopt: Error while setting state {:?}", e);
+                }
+            }
+        }
+
+        let props = record.container_props.clone();
+        let launch_params = LaunchParams {
+            uri: app_manifest.start_page.to_string(),
+            browser_name: record.browser_name.clone(),
+            runtime,
+            resident,
+            name: props.name.clone(),
+            suspend: false,
+            requires_focus: props.requires_focus,
+            x: props.dimensions.x,
+            y: props.dimensions.y,
+            w: props.dimensions.w,
+            h: props.dimensions.h,
+            properties: app_manifest.properties.clone(),
+        };
+        let always_retained = state
+            .config
+            .retention_policy
+            .always_retained
+            .contains(&record.app_id);
+        ViewManager::adopt_view(state, record.browser_name, props.view_id);
+        state.app_launcher_state.add_app(
+            key,
+            App {
+                manifest: app_manifest,
+                state: record.state,
+                app_id: record.app_id,
+                initial_intent: record.initial_intent,
+                current_intent: record.current_intent,
+                launch_params,
+                ready: record.state != LifecycleState::Initializing,
+                container_props: props,
+                session_id: "none".into(),
+                always_retained,
+                launch_time: record.launch_time,
+                on_destroyed_action: None,
+                instance: record.instance,
+            },
+        );
+        Ok(AppManagerResponse::None)
+    }
+
     pub async fn ready(
         state: &LauncherState,
         app_id: &str,
     ) -> Result<AppManagerResponse, AppError> {
-        let entry = state.app_launcher_state.get_app_by_id(app_id);
+        let entry = state
+            .app_launcher_state
+            .find_instance_in_state(app_id, LifecycleState::Initializing);
         match entry {
-            Some(app) => match app.state {
-                LifecycleState::Initializing => {
-                    state.app_launcher_state.set_app_ready(app_id);
-                    Self::set_state(state.clone(), app_id.into(), LifecycleState::Inactive).await
-                }
-                _ => Err(AppError::UnexpectedState),
-            },
+            Some(app) => {
+                let key = app.container_props.name;
+                state.app_launcher_state.set_app_ready(&key);
+                Self::set_state(state.clone(), key, LifecycleState::Inactive).await
+            }
             None => Err(AppError::UnexpectedState),
         }
     }
@@ -792,7 +1196,7 @@ impl AppLauncher {
         if let Some(action) = app.on_destroyed_action {
             match action {
                 OnDestroyedAction::Launch(request) => {
-                    Self::launch(&state, request).await.ok();
+                    Self::launch_app(&state, request).await.ok();
                 }
             }
         }
@@ -838,3 +1242,65 @@ impl AppLauncher {
         Err(AppError::General)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ripple_sdk::{
+        api::{
+            config::LauncherConfig, firebolt::fb_discovery::LaunchRequest,
+            manifest::device_manifest::DeviceManifest,
+        },
+        crossbeam::channel::unbounded,
+        extn::{
+            client::{extn_client::ExtnClient, extn_sender::ExtnSender},
+            extn_id::ExtnId,
+        },
+        tokio,
+        utils::error::RippleError,
+    };
+
+    use super::*;
+
+    fn launcher_state() -> Result<LauncherState, RippleError> {
+        let (_, manifest) = DeviceManifest::load_from_content(
+            include_str!("../../../../examples/manifest/device-manifest-example.json").to_owned(),
+        )?;
+        let saved_dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+        let config = LauncherConfig {
+            lifecycle_policy: manifest.get_lifecycle_policy(),
+            retention_policy: manifest.get_retention_policy(),
+            app_library_state: Default::default(),
+            saved_dir: format!("{}/", saved_dir.display()),
+            runtime_registry: manifest.get_runtime_registry(),
+        };
+        let (tx, rx) = unbounded();
+        let client = ExtnClient::new(
+            rx,
+            ExtnSender::new(
+                tx,
+                ExtnId::get_main_target("test".into()),
+                Vec::new(),
+                Vec::new(),
+            ),
+        );
+        Ok(LauncherState::from_config(config, client))
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn test_retry_after_failed_launch() {
+        let state = launcher_state().unwrap();
+        let request = LaunchRequest {
+            app_id: "missing".into(),
+            intent: None,
+        };
+        assert!(matches!(
+            AppLauncher::launch(&state, request.clone()).await,
+            Err(AppError::NotFound)
+        ));
+        // the retry is not ignored as a repeat of the failed launch
+        assert!(matches!(
+            AppLauncher::launch(&state, request).await,
+            Err(AppError::NotFound)
+        ));
+    }
+}
@@ -24,7 +24,9 @@ use ripple_sdk::{
     api::{
         apps::{Dimensions, StateChange, ViewId},
         firebolt::fb_lifecycle::LifecycleState,
+        manifest::apps::ContainerLayer,
     },
+    framework::file_store::FileStore,
     log::{debug, error},
 };
 use serde::{Deserialize, Serialize};
@@ -52,27 +54,106 @@ pub struct ContainerProperties {
     pub view_id: ViewId,
     pub requires_focus: bool,
     pub dimensions: Dimensions,
+    #[serde(default)]
+    pub layer: ContainerLayer,
+}
+
+/// Container stack as persisted under the saved directory so it can be recovered
+/// after a restart.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PersistedContainers {
+    pub stack: Stack,
+    pub containers: HashMap<String, ContainerProperties>,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct ContainerState {
     stack: Arc<RwLock<Stack>>,
     containers: Arc<RwLock<HashMap<String, ContainerProperties>>>,
+    store: Option<Arc<RwLock<FileStore<PersistedContainers>>>>,
 }
 
 impl ContainerState {
-    fn get_prev_stack(&self) -> Option<String> {
-        let prev_container = {
-            let stack = self.stack.read().unwrap();
-            let r = stack.peek().clone();
-            if r.is_none() {
-                None
-            } else {
-                let v = r.unwrap().clone();
-                Some(v)
-            }
+    pub fn new(saved_dir: &str) -> ContainerState {
+        let path = format!("{}launcher_containers", saved_dir);
+        let store = if let Ok(v) = FileStore::load(path.clone()) {
+            v
+        } else {
+            FileStore::new(path, PersistedContainers::default())
         };
-        prev_container
+        ContainerState {
+            store: Some(Arc::new(RwLock::new(store))),
+            ..Default::default()
+        }
+    }
+
+    /// Containers persisted by the previous run, available until the first change is synced.
+    pub fn get_persisted(&self) -> PersistedContainers {
+        match &self.store {
+            Some(store) => store.read().unwrap().value.clone(),
+            None => PersistedContainers::default(),
+        }
+    }
+
+    fn sync(&self) {
+        if let Some(store) = &self.store {
+            let mut store = store.write().unwrap();
+            store.value = PersistedContainers {
+                stack: self.stack.read().unwrap().clone(),
+                containers: self.containers.read().unwrap().clone(),
+            };
+            store.sync();
+        }
+    }
+
+    pub fn restore(&self, persisted: PersistedContainers) {
+        *self.stack.write().unwrap() = persisted.stack;
+        *self.containers.write().unwrap() = persisted.containers;
+        self.sync();
+    }
+
+    /// Top most container of a layer which can take focus.
+    fn get_focused_container(&self) -> Option<String> {
+        let stack = self.stack.read().unwrap().items();
+        let containers = self.containers.read().unwrap();
+        stack
+            .into_iter()
+            .rev()
+            .find(|name| match containers.get(name) {
+                Some(props) => props.layer.takes_focus(),
+                None => false,
+            })
+    }
+
+    fn get_top_of_layer(&self, layer: ContainerLayer) -> Option<ContainerProperties> {
+        let stack = self.stack.read().unwrap().items();
+        let containers = self.containers.read().unwrap();
+        stack
+            .iter()
+            .rev()
+            .filter_map(|name| containers.get(name))
+            .find(|props| props.layer == layer)
+            .cloned()
+    }
+
+    fn get_containers_above(&self, name: &str) -> Vec<ContainerProperties> {
+        let stack = self.stack.read().unwrap().items();
+        let containers = self.containers.read().unwrap();
+        stack
+            .iter()
+            .skip_while(|n| n.as_str() != name)
+            .skip(1)
+            .filter_map(|n| containers.get(n).cloned())
+            .collect()
+    }
+
+    /// Keeps every container above all containers of lower layers.
+    fn order_stack(&self) {
+        let containers = self.containers.read().unwrap();
+        self.stack
+            .write()
+            .unwrap()
+            .sort_by_key(|name| containers.get(name).map(|p| p.layer).unwrap_or_default());
     }
 
     fn get_container_by_name(&self, id: &String) -> Option<ContainerProperties> {
@@ -89,13 +170,19 @@ impl ContainerState {
     }
 
     fn add_container(&self, k: String, v: ContainerProperties) {
-        let mut containers = self.containers.write().unwrap();
-        containers.insert(k, v);
+        {
+            let mut containers = self.containers.write().unwrap();
+            containers.insert(k, v);
+        }
+        self.sync();
     }
 
     fn remove_container(&self, k: String) {
-        let mut containers = self.containers.write().unwrap();
-        containers.remove(&k);
+        {
+            let mut containers = self.containers.write().unwrap();
+            containers.remove(&k);
+        }
+        self.sync();
     }
 
     fn contains_stack_by_name(&self, id: &String) -> bool {
@@ -109,23 +196,26 @@ impl ContainerState {
     }
 
     fn add_stack(&self, id: String) {
-        let mut stack = self.stack.write().unwrap();
-        stack.push(id);
+        self.stack.write().unwrap().push(id);
+        self.order_stack();
+        self.sync();
     }
 
     fn pop_stack_by_name(&self, name: &String) {
-        let mut stack = self.stack.write().unwrap();
-        stack.pop_item(name);
+        self.stack.write().unwrap().pop_item(name);
+        self.sync();
     }
 
     fn bring_stack_to_front(&self, name: &str) {
-        let mut stack = self.stack.write().unwrap();
-        stack.bring_to_front(name);
+        self.stack.write().unwrap().bring_to_front(name);
+        self.order_stack();
+        self.sync();
     }
 
     fn send_stack_to_back(&self, name: &str) {
-        let mut stack = self.stack.write().unwrap();
-        stack.send_to_back(name);
+        self.stack.write().unwrap().send_to_back(name);
+        self.order_stack();
+        self.sync();
     }
 }
 
@@ -139,7 +229,7 @@ impl ContainerManager {
         let name = props.name.clone();
         println!("add: name={}", name);
         let mut prev_props = None;
-        let prev_container = state.container_state.get_prev_stack();
+        let prev_container = state.container_state.get_focused_container();
         if let Some(pc) = prev_container {
             if !pc.eq(name.as_str()) {
                 if let Some(pp) = state.container_state.get_container_by_name(&pc) {
@@ -148,15 +238,22 @@ impl ContainerManager {
             }
         }
 
-        if !state.container_state.contains_stack_by_name(&name) {
-            state.container_state.add_stack(name.clone());
-        }
         state
             .container_state
             .add_container(name.clone(), props.clone());
+        if !state.container_state.contains_stack_by_name(&name) {
+            state.container_state.add_stack(name.clone());
+        }
         AppLauncher::on_container_event(state, ContainerEvent::Added(props.clone())).await;
         Self::bring_to_front(&state, &name).await.ok();
-        AppLauncher::on_container_event(state, ContainerEvent::Focused(prev_props, Some(props)))
+        let next_props = match props.layer.takes_focus() {
+            true => Some(props),
+            false => state
+                .container_state
+                .get_focused_container()
+                .and_then(|n| state.container_state.get_container_by_name(&n)),
+        };
+        AppLauncher::on_container_event(state, ContainerEvent::Focused(prev_props, next_props))
             .await;
         Self::set_visible(&state, &name, true).await
     }
@@ -173,7 +270,7 @@ impl ContainerManager {
             }
             state.container_state.pop_stack_by_name(&name.into());
             let mut next_props = None;
-            if let Some(nc) = state.container_state.get_prev_stack() {
+            if let Some(nc) = state.container_state.get_focused_container() {
                 if let Some(np) = state.container_state.get_container_by_name(&nc) {
                     next_props = Some(np.clone());
                 }
@@ -226,6 +323,12 @@ impl ContainerManager {
             println!("bring_to_front: error: req_id={:?}", e);
             return Err(ContainerError::General);
         }
+        // Restore the z-order of the higher layers
+        for above in state.container_state.get_containers_above(name) {
+            if let Err(e) = ViewManager::set_position(state, above.view_id, Position::Front).await {
+                error!("bring_to_front: Failed to raise {}: e={:?}", above.name, e);
+            }
+        }
         match Self::focus_top_container(&state).await {
             Ok(v) => Ok(ResultType::Uuid(v)),
             Err(e) => Err(e),
@@ -233,7 +336,7 @@ impl ContainerManager {
     }
 
     async fn focus_top_container(state: &LauncherState) -> Result<ViewId, ContainerError> {
-        let item = state.container_state.get_prev_stack();
+        let item = state.container_state.get_focused_container();
 
         if let None = item {
             return Err(ContainerError::NotFound);
@@ -289,9 +392,10 @@ impl ContainerManager {
 
         Self::focus_top_container(&state).await.ok();
         let mut next_props = None;
-        let name = state.container_state.get_prev_stack().clone().unwrap();
-        if let Some(n) = state.container_state.get_container_by_name(&name) {
-            next_props = Some(n.clone());
+        if let Some(name) = state.container_state.get_focused_container() {
+            if let Some(n) = state.container_state.get_container_by_name(&name) {
+                next_props = Some(n.clone());
+            }
         }
         AppLauncher::on_container_event(state, ContainerEvent::Focused(Some(props), next_props))
             .await;
@@ -335,6 +439,12 @@ impl ContainerManager {
         result
     }
 
+    /// Top most container of the main layer, this app remains in the foreground while an
+    /// overlay holds focus.
+    pub fn get_top_main_container(state: &LauncherState) -> Option<ContainerProperties> {
+        state.container_state.get_top_of_layer(ContainerLayer::Main)
+    }
+
     pub async fn on_state_changed(state: &LauncherState, state_change: StateChangeInternal) {
         debug!("on_app_state_change: state_change={:?}", state_change);
         let app_id = state_change.container_props.name.clone();
@@ -16,8 +16,9 @@
 //
 
 use ripple_sdk::log::debug;
+use serde::{Deserialize, Serialize};
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct Stack {
     stack: Vec<String>,
 }
@@ -60,4 +61,13 @@ impl Stack {
     pub fn len(&mut self) -> usize {
         self.stack.len()
     }
+
+    /// Stable sort of the stack, items with equal keys keep their relative order.
+    pub fn sort_by_key<K: Ord>(&mut self, f: impl FnMut(&String) -> K) {
+        self.stack.sort_by_key(f);
+    }
+
+    pub fn items(&self) -> Vec<String> {
+        self.stack.clone()
+    }
 }
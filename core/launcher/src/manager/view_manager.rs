@@ -60,8 +60,8 @@ pub struct LaunchParams {
     // reflects the JSON contents of a file in order to demonstrate app launches.
     pub uri: String,
     pub browser_name: String,
-    #[serde(rename = "type")]
-    pub _type: String,
+    pub runtime: String,
+    pub resident: bool,
     pub suspend: bool,
     pub requires_focus: bool,
     pub name: String,
@@ -111,28 +111,6 @@ pub enum ViewError {
     IoError,
 }
 
-#[derive(Debug, Serialize, Deserialize, Copy, Clone)]
-pub enum Fnci {
-    Web,
-    Lightning,
-    Netflix,
-    Cobalt,
-    PrimeVideo,
-    DisneyPlus,
-}
-
-pub fn get_fnci(runtime: &str) -> Option<Fnci> {
-    match runtime {
-        "web" => Some(Fnci::Web),
-        "lightning" => Some(Fnci::Lightning),
-        "netflix" => Some(Fnci::Netflix),
-        "cobalt" => Some(Fnci::Cobalt),
-        "primevideo" => Some(Fnci::PrimeVideo),
-        "disneyplus" => Some(Fnci::DisneyPlus),
-        _ => None,
-    }
-}
-
 pub struct ViewManager;
 
 #[derive(Debug, Clone, Default)]
@@ -176,7 +154,8 @@ impl ViewManager {
             .send_extn_request(BrowserRequest::Start(BrowserLaunchParams {
                 uri: params.uri,
                 browser_name: params.browser_name.clone(),
-                _type: params._type,
+                runtime: params.runtime,
+                resident: params.resident,
                 visible: false,
                 suspend: params.suspend,
                 focused: params.requires_focus,
@@ -204,6 +183,12 @@ impl ViewManager {
         result
     }
 
+    /// Registers a view that is already loaded in the window manager, used when
+    /// re-adopting apps after a restart.
+    pub fn adopt_view(state: &LauncherState, browser_name: String, id: ViewId) {
+        state.view_state.insert_view(browser_name, id);
+    }
+
     pub async fn release_view(state: &LauncherState, id: ViewId) -> Result<ViewId, ViewError> {
         let mut result = Err(ViewError::NotFound);
         if let Some(name) = state.view_state.get_name(id) {
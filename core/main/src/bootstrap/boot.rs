@@ -25,6 +25,7 @@ use super::{
         load_extn_step::LoadExtensionsStep, load_session_step::LoadDistributorValuesStep,
         start_cloud_sync_step::StartCloudSyncStep, start_extn_channel_step::StartExtnChannelsStep,
     },
+    load_store_cipher_step::LoadStoreCipherStep,
     setup_extn_client_step::SetupExtnClientStep,
     start_app_manager_step::StartAppManagerStep,
     start_fbgateway_step::FireboltGatewayStep,
@@ -40,20 +41,24 @@ use super::{
 ///
 /// # Steps
 ///
-/// 1. [SetupExtnClientStep] - Initializes the extn client to start the Inter process communication backbone
-/// 2. [LoadExtensionMetadataStep] - Loads the Extn metadata from the So files
-/// 3. [LoadExtensionsStep] - Loads the Extensions in to [crate::state::extn_state::ExtnState]
-/// 4. [StartExtnChannelsStep] - Starts the Device channel extension
-/// 5. [StartAppManagerStep] - Starts the App Manager and other supporting services
-/// 6. [LoadDistributorValuesStep] - Loads the values from distributor like Session
-/// 7. [CheckLauncherStep] - Checks the presence of launcher extension and starts default app
-/// 8. [StartWsStep] - Starts the Websocket to accept external and internal connections
-/// 9. [FireboltGatewayStep] - Starts the firebolt gateway and blocks the thread to keep it alive till interruption.
+/// 1. [LoadStoreCipherStep] - Loads the grant and permission stores encrypted at rest
+/// 2. [SetupExtnClientStep] - Initializes the extn client to start the Inter process communication backbone
+/// 3. [LoadExtensionMetadataStep] - Loads the Extn metadata from the So files
+/// 4. [LoadExtensionsStep] - Loads the Extensions in to [crate::state::extn_state::ExtnState]
+/// 5. [StartExtnChannelsStep] - Starts the Device channel extension
+/// 6. [StartAppManagerStep] - Starts the App Manager and other supporting services
+/// 7. [LoadDistributorValuesStep] - Loads the values from distributor like Session
+/// 8. [CheckLauncherStep] - Checks the presence of launcher extension and starts default app
+/// 9. [StartWsStep] - Starts the Websocket to accept external and internal connections
+/// 10. [FireboltGatewayStep] - Starts the firebolt gateway and blocks the thread to keep it alive till interruption.
 
 ///
 pub async fn boot(state: BootstrapState) {
     let bootstrap = &Bootstrap::new(state);
     bootstrap
+        .step(LoadStoreCipherStep)
+        .await
+        .expect("Store cipher load failure")
         .step(SetupExtnClientStep)
         .await
         .expect("Extn Client setup failure")
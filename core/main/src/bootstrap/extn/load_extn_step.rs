@@ -16,7 +16,7 @@
 //
 
 use ripple_sdk::{
-    api::firebolt::fb_openrpc::FireboltVersionManifest,
+    api::firebolt::fb_openrpc::{FireboltOpenRpc, FireboltOpenRpcMethod, FireboltVersionManifest},
     async_trait::async_trait,
     extn::{
         client::extn_sender::ExtnSender,
@@ -24,7 +24,7 @@ use ripple_sdk::{
         ffi::{ffi_channel::load_channel_builder, ffi_jsonrpsee::load_jsonrpsee_methods},
     },
     framework::bootstrap::Bootstep,
-    log::{debug, error, info},
+    log::{debug, error, info, warn},
     utils::error::RippleError,
 };
 
@@ -33,6 +33,7 @@ use crate::state::{
     extn_state::PreLoadedExtnChannel,
 };
 use jsonrpsee::core::server::rpc_module::Methods;
+use std::collections::HashSet;
 
 /// Actual bootstep which loads the extensions into the ExtnState.
 /// Currently this step loads
@@ -52,6 +53,7 @@ impl Bootstep<BootstrapState> for LoadExtensionsStep {
         let mut jsonrpsee_extns: Methods = Methods::new();
         let mut open_rpcs: Vec<FireboltVersionManifest> = Vec::new();
         let main_sender = state.clone().extn_state.get_sender();
+        let open_rpc_state = state.platform_state.open_rpc_state.clone();
         for extn in loaded_extensions.iter() {
             unsafe {
                 let path = extn.entry.clone().path;
@@ -104,11 +106,31 @@ impl Bootstep<BootstrapState> for LoadExtensionsStep {
                                 extension.clone().uses,
                                 extension.clone().fulfills,
                             );
-                            if let Some(open_rpc) = (builder.get_extended_capabilities)() {
-                                open_rpcs.push(open_rpc)
+                            let methods = (builder.build)(extn_sender, tr);
+                            let mut gated_methods = HashSet::new();
+                            match (builder.get_extended_capabilities)() {
+                                Some(open_rpc) if open_rpc.get_latest_rpc().is_some() => {
+                                    let rpc: FireboltOpenRpc = open_rpc.clone().into();
+                                    gated_methods.extend(rpc.get_methods_caps().into_keys());
+                                    open_rpcs.push(open_rpc)
+                                }
+                                Some(_) => error!("empty open rpc fragment in {}", path),
+                                None => {}
+                            }
+                            for method in methods.method_names() {
+                                let method =
+                                    FireboltOpenRpcMethod::name_with_lowercase_module(method);
+                                if !gated_methods.contains(&method)
+                                    && open_rpc_state.get_caps_for_method(method.clone()).is_none()
+                                {
+                                    warn!(
+                                        "{} has no capabilities for {}, calls will be unsupported",
+                                        extension.id, method
+                                    );
+                                }
                             }
 
-                            let _ = jsonrpsee_extns.merge((builder.build)(extn_sender, tr));
+                            let _ = jsonrpsee_extns.merge(methods);
                         }
                     }
                 }
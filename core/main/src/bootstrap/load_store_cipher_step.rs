@@ -0,0 +1,57 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+use ripple_sdk::{
+    async_trait::async_trait,
+    framework::{bootstrap::Bootstep, store_cipher::StoreCipher, RippleResponse},
+    log::{error, info},
+};
+
+use crate::state::bootstrap_state::BootstrapState;
+
+/// Creates the cipher for the stores encrypted at rest and loads the grant and permission
+/// stores with it. When the device key is unavailable they are kept in memory.
+pub struct LoadStoreCipherStep;
+
+#[async_trait]
+impl Bootstep<BootstrapState> for LoadStoreCipherStep {
+    fn get_name(&self) -> String {
+        "LoadStoreCipherStep".into()
+    }
+
+    async fn setup(&self, s: BootstrapState) -> RippleResponse {
+        let manifest = s.platform_state.get_device_manifest();
+        let config = match &manifest.configuration.store_encryption {
+            Some(config) => config,
+            None => return Ok(()),
+        };
+        match StoreCipher::from_config(config, &manifest.configuration.saved_dir) {
+            Ok(cipher) => {
+                let cap_state = &s.platform_state.cap_state;
+                cap_state
+                    .grant_state
+                    .load_encrypted(&manifest, cipher.clone());
+                cap_state.permitted_state.load_encrypted(&manifest, cipher);
+                info!("Loaded the stores encrypted with the device key");
+            }
+            Err(e) => error!(
+                "Device key unavailable, grants and permissions won't persist {:?}",
+                e
+            ),
+        }
+        Ok(())
+    }
+}
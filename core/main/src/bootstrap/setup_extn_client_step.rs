@@ -28,8 +28,10 @@ use crate::{
     processor::{
         app_events_processor::AppEventsProcessor,
         authorized_info_processor::AuthorizedInfoProcessor,
-        config_processor::ConfigRequestProcessor, exn_status_processor::ExtnStatusProcessor,
-        keyboard_processor::KeyboardProcessor, pin_processor::PinProcessor,
+        config_processor::ConfigRequestProcessor,
+        device_condition_processor::DeviceConditionProcessor,
+        exn_status_processor::ExtnStatusProcessor, keyboard_processor::KeyboardProcessor,
+        pin_processor::PinProcessor, power_state_processor::PowerStateProcessor,
         storage::storage_manager_processor::StorageManagerProcessor,
     },
     state::bootstrap_state::BootstrapState,
@@ -52,6 +54,8 @@ impl Bootstep<BootstrapState> for SetupExtnClientStep {
         client.add_request_processor(KeyboardProcessor::new(state.platform_state.clone()));
         client.add_event_processor(ExtnStatusProcessor::new(state.clone().extn_state));
         client.add_event_processor(AppEventsProcessor::new(state.platform_state.clone()));
+        client.add_event_processor(PowerStateProcessor::new(state.platform_state.clone()));
+        client.add_event_processor(DeviceConditionProcessor::new(state.platform_state.clone()));
         client.add_request_processor(StorageManagerProcessor::new(state.platform_state.clone()));
         client.add_request_processor(StoreUserGrantsProcessor::new(state.platform_state.clone()));
         client.add_request_processor(StorePrivacySettingsProcessor::new(
@@ -16,13 +16,23 @@
 //
 
 use ripple_sdk::async_trait::async_trait;
-use ripple_sdk::{framework::bootstrap::Bootstep, tokio, utils::error::RippleError};
+use ripple_sdk::{
+    api::device::device_events::{DeviceEvent, DeviceEventCallback, DeviceEventRequest},
+    framework::bootstrap::Bootstep,
+    log::warn,
+    tokio,
+    utils::error::RippleError,
+};
 
 use crate::{
-    service::apps::delegated_launcher_handler::DelegatedLauncherHandler,
+    service::{
+        apps::delegated_launcher_handler::DelegatedLauncherHandler, user_grants::GrantState,
+    },
     state::bootstrap_state::BootstrapState,
 };
 
+const DEVICE_EVENT_LISTENER_ID: &str = "ripple";
+
 /// Starts the App Manager and other supporting services
 pub struct StartAppManagerStep;
 
@@ -33,6 +43,44 @@ impl Bootstep<BootstrapState> for StartAppManagerStep {
     }
 
     async fn setup(&self, state: BootstrapState) -> Result<(), RippleError> {
+        // Power active user grants are cleared when the device leaves the on state
+        if let Err(e) = state
+            .platform_state
+            .get_client()
+            .send_extn_request(DeviceEventRequest {
+                event: DeviceEvent::SystemPowerStateChanged,
+                subscribe: true,
+                id: DEVICE_EVENT_LISTENER_ID.into(),
+                callback_type: DeviceEventCallback::ExtnEvent,
+            })
+            .await
+        {
+            warn!("Couldnt listen to power state changes {:?}", e);
+        }
+        // Capability availability follows the device conditions it is configured to depend on
+        let conditions = state
+            .platform_state
+            .get_device_manifest()
+            .capabilities
+            .availability_conditions;
+        for condition in conditions.keys() {
+            if let Err(e) = state
+                .platform_state
+                .get_client()
+                .send_extn_request(DeviceEventRequest {
+                    event: condition.get_device_event(),
+                    subscribe: true,
+                    id: DEVICE_EVENT_LISTENER_ID.into(),
+                    callback_type: DeviceEventCallback::ExtnEvent,
+                })
+                .await
+            {
+                warn!("Couldnt listen to {:?} changes {:?}", condition, e);
+            }
+        }
+        tokio::spawn(GrantState::run_expiry_scheduler(
+            state.platform_state.clone(),
+        ));
         let mut app_manager =
             DelegatedLauncherHandler::new(state.channels_state, state.platform_state);
         tokio::spawn(async move {
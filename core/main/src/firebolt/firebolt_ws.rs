@@ -209,7 +209,7 @@ impl FireboltWs {
         );
         let msg = FireboltGatewayCommand::RegisterSession {
             session_id: identity.session_id.clone(),
-            session,
+            session: session.clone(),
         };
         if let Err(e) = client.send_gateway_command(msg) {
             error!("Error registering the connection {:?}", e);
@@ -232,7 +232,17 @@ impl FireboltWs {
                 }
             }
         });
-        while let Some(msg) = receiver.next().await {
+        loop {
+            let msg = tokio::select! {
+                msg = receiver.next() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                _ = session.disconnected() => {
+                    info!("Closing session of {}", ctx.app_id);
+                    break;
+                }
+            };
             if let Ok(msg) = msg {
                 if msg.is_text() && !msg.is_empty() {
                     let req_text = String::from(msg.to_text().unwrap());
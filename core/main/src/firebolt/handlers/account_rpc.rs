@@ -29,11 +29,15 @@ use ripple_sdk::{
     },
     extn::extn_client_message::ExtnResponse,
     log::error,
+    tokio,
 };
 
 use crate::{
     firebolt::rpc::RippleRPCProvider,
-    state::{cap::cap_state::CapState, platform_state::PlatformState},
+    state::{
+        cap::{cap_state::CapState, permitted_state::PermissionHandler},
+        platform_state::PlatformState,
+    },
     utils::rpc_utils::rpc_err,
 };
 
@@ -78,6 +82,8 @@ impl AccountServer for AccountImpl {
                         None,
                     )
                     .await;
+                    // Permissions are bound to the account session
+                    tokio::spawn(PermissionHandler::refresh_all(self.platform_state.clone()));
                     Ok(())
                 }
                 _ => {
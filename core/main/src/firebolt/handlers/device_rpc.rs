@@ -21,7 +21,10 @@ use crate::{
     firebolt::rpc::RippleRPCProvider,
     processor::storage::storage_manager::StorageManager,
     service::apps::app_events::AppEvents,
-    state::platform_state::PlatformState,
+    state::{
+        cap::permitted_state::PermissionHandler, metrics_state::MetricsState,
+        platform_state::PlatformState,
+    },
     utils::rpc_utils::{rpc_add_event_listener, rpc_err},
 };
 
@@ -60,7 +63,7 @@ use ripple_sdk::{
     },
     extn::extn_client_message::ExtnResponse,
     log::error,
-    tokio::time::timeout,
+    tokio::{self, time::timeout},
 };
 
 include!(concat!(env!("OUT_DIR"), "/version.rs"));
@@ -721,7 +724,13 @@ impl DeviceServer for DeviceImpl {
             .await;
         match resp {
             Ok(payload) => match payload.payload.extract().unwrap() {
-                ExtnResponse::None(()) => Ok(()),
+                ExtnResponse::None(()) => {
+                    // Permissions and the metrics context are bound to the account session
+                    tokio::spawn(PermissionHandler::refresh_all(self.state.clone()));
+                    let state = self.state.clone();
+                    tokio::spawn(async move { MetricsState::refresh_context(&state).await });
+                    Ok(())
+                }
                 _ => Err(rpc_err("Provision Status error response TBD")),
             },
             Err(_e) => Err(jsonrpsee::core::Error::Custom(String::from(
@@ -0,0 +1,51 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use crate::{
+    firebolt::rpc::RippleRPCProvider, service::apps::event_delivery::EventMetrics,
+    state::platform_state::PlatformState,
+};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc, RpcModule};
+use ripple_sdk::{api::gateway::rpc_gateway_api::CallContext, async_trait::async_trait};
+
+#[rpc(server)]
+pub trait Diagnostics {
+    #[method(name = "diagnostics.eventMetrics")]
+    async fn event_metrics(&self, ctx: CallContext) -> RpcResult<EventMetrics>;
+}
+
+pub struct DiagnosticsImpl {
+    pub platform_state: PlatformState,
+}
+
+#[async_trait]
+impl DiagnosticsServer for DiagnosticsImpl {
+    async fn event_metrics(&self, _ctx: CallContext) -> RpcResult<EventMetrics> {
+        Ok(self.platform_state.app_events_state.delivery.get_metrics())
+    }
+}
+
+pub struct DiagnosticsRPCProvider;
+
+impl RippleRPCProvider<DiagnosticsImpl> for DiagnosticsRPCProvider {
+    fn provide(state: PlatformState) -> RpcModule<DiagnosticsImpl> {
+        (DiagnosticsImpl {
+            platform_state: state,
+        })
+        .into_rpc()
+    }
+}
@@ -547,7 +547,8 @@ impl DiscoveryServer for DiscoveryImpl {
     ) -> RpcResult<ContentEntityResponse> {
         let parameters = entity_request.parameters;
         let federated_options = entity_request.options.unwrap_or_default();
-        let (session_tx, session_rx) = oneshot::channel::<ProviderResponsePayload>();
+        let (session_tx, session_rx) =
+            oneshot::channel::<provider_broker::ProviderBrokerResponse>();
         let pr_msg = provider_broker::ProviderBrokerRequest {
             app_id: Some(entity_request.provider.to_owned()),
             capability: FireboltCap::Short(ENTITY_INFO_CAPABILITY.into()).as_str(),
@@ -568,7 +569,7 @@ impl DiscoveryServer for DiscoveryImpl {
             Error::Custom(String::from(
                 "Error returning back from entity response provider",
             ))
-        })?;
+        })??;
         match result.as_entity_info_result() {
             Some(res) => Ok(ContentEntityResponse {
                 provider: entity_request.provider.to_owned(),
@@ -627,7 +628,8 @@ impl DiscoveryServer for DiscoveryImpl {
     ) -> RpcResult<ProvidedPurchasedContentResult> {
         let parameters = entity_request.parameters;
         let federated_options = entity_request.options.unwrap_or_default();
-        let (session_tx, session_rx) = oneshot::channel::<ProviderResponsePayload>();
+        let (session_tx, session_rx) =
+            oneshot::channel::<provider_broker::ProviderBrokerResponse>();
         let pr_msg = provider_broker::ProviderBrokerRequest {
             app_id: Some(entity_request.provider.to_owned()),
             capability: FireboltCap::Short(PURCHASED_CONTENT_CAPABILITY.into()).as_str(),
@@ -648,7 +650,7 @@ impl DiscoveryServer for DiscoveryImpl {
             Error::Custom(String::from(
                 "Error returning back from entity response provider",
             ))
-        })?;
+        })??;
         match result.as_purchased_content_result() {
             Some(res) => Ok(ProvidedPurchasedContentResult {
                 provider: entity_request.provider.to_owned(),
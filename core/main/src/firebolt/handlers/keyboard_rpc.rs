@@ -17,7 +17,9 @@
 
 use crate::{
     firebolt::rpc::RippleRPCProvider,
-    service::apps::provider_broker::{ProviderBroker, ProviderBrokerRequest},
+    service::apps::provider_broker::{
+        ProviderBroker, ProviderBrokerRequest, ProviderBrokerResponse,
+    },
     state::platform_state::PlatformState,
 };
 use jsonrpsee::{
@@ -35,7 +37,7 @@ use ripple_sdk::{
                 KeyboardSessionRequest, KeyboardSessionResponse, KeyboardType, EMAIL_EVENT_PREFIX,
                 KEYBOARD_PROVIDER_CAPABILITY, PASSWORD_EVENT_PREFIX, STANDARD_EVENT_PREFIX,
             },
-            provider::{FocusRequest, ProviderRequestPayload, ProviderResponsePayload},
+            provider::{FocusRequest, ProviderRequestPayload},
         },
         gateway::rpc_gateway_api::CallContext,
     },
@@ -264,7 +266,7 @@ impl KeyboardImpl {
             ctx: ctx.clone(),
             message: request.message,
         };
-        let (session_tx, session_rx) = oneshot::channel::<ProviderResponsePayload>();
+        let (session_tx, session_rx) = oneshot::channel::<ProviderBrokerResponse>();
         let pr_msg = ProviderBrokerRequest {
             // TODO which capability this rpc method providers should come from firebolt spec
             capability: KEYBOARD_PROVIDER_CAPABILITY.to_string(),
@@ -276,12 +278,13 @@ impl KeyboardImpl {
         };
         ProviderBroker::invoke_method(&self.platform_state, pr_msg).await;
         match session_rx.await {
-            Ok(result) => match result.as_keyboard_result() {
+            Ok(Ok(result)) => match result.as_keyboard_result() {
                 Some(res) => Ok(res),
                 None => Err(Error::Custom(String::from(
                     "Invalid response back from provider",
                 ))),
             },
+            Ok(Err(e)) => Err(e.into()),
             Err(_) => Err(Error::Custom(String::from(
                 "Error returning back from keyboard provider",
             ))),
@@ -45,7 +45,8 @@ use ripple_sdk::{
 use serde::Deserialize;
 
 use crate::{
-    firebolt::rpc::RippleRPCProvider, state::platform_state::PlatformState,
+    firebolt::rpc::RippleRPCProvider,
+    state::{metrics_state::MetricsState, platform_state::PlatformState},
     utils::rpc_utils::rpc_err,
 };
 
@@ -329,13 +330,8 @@ impl MetricsServer for MetricsImpl {
         });
 
         trace!("metrics.startContent={:?}", start_content);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(start_content.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, start_content).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -350,13 +346,8 @@ impl MetricsServer for MetricsImpl {
             entity_id: stop_content_params.entity_id,
         });
         trace!("metrics.stopContent={:?}", stop_content);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(stop_content.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, stop_content).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -366,13 +357,8 @@ impl MetricsServer for MetricsImpl {
             page_id: page_params.page_id,
         });
         trace!("metrics.page={:?}", page);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(page.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, page).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -389,13 +375,8 @@ impl MetricsServer for MetricsImpl {
         });
         trace!("metrics.action={:?}", action);
 
-        match self
-            .state
-            .get_client()
-            .send_extn_request(action.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, action).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -405,13 +386,8 @@ impl MetricsServer for MetricsImpl {
             ttmu_ms: 12,
         };
         trace!("metrics.action = {:?}", data);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(BehavioralMetricRequest::Ready(data))
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, BehavioralMetricRequest::Ready(data)).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -427,13 +403,8 @@ impl MetricsServer for MetricsImpl {
             parameters: error_params.parameters.clone(),
         });
         trace!("metrics.error={:?}", error_message);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(error_message.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, error_message).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -444,12 +415,11 @@ impl MetricsServer for MetricsImpl {
             app_session_id: Some(ctx.session_id),
         };
         trace!("metrics.action = {:?}", data);
-        Ok(self
-            .state
-            .get_client()
-            .send_extn_request(BehavioralMetricRequest::TelemetrySignIn(data))
-            .await
-            .is_ok())
+        Ok(
+            MetricsState::send_metric(&self.state, BehavioralMetricRequest::TelemetrySignIn(data))
+                .await
+                .is_ok(),
+        )
     }
 
     async fn sign_out(&self, ctx: CallContext) -> RpcResult<bool> {
@@ -459,12 +429,11 @@ impl MetricsServer for MetricsImpl {
             app_session_id: Some(ctx.session_id),
         };
         trace!("metrics.action = {:?}", data);
-        Ok(self
-            .state
-            .get_client()
-            .send_extn_request(BehavioralMetricRequest::TelemetrySignOut(data))
-            .await
-            .is_ok())
+        Ok(
+            MetricsState::send_metric(&self.state, BehavioralMetricRequest::TelemetrySignOut(data))
+                .await
+                .is_ok(),
+        )
     }
 
     async fn internal_initialize(
@@ -479,11 +448,11 @@ impl MetricsServer for MetricsImpl {
             semantic_version: internal_initialize_params.value.to_string(),
         };
         trace!("metrics.action = {:?}", data);
-        let _ = self
-            .state
-            .get_client()
-            .send_extn_request(BehavioralMetricRequest::TelemetryInternalInitialize(data))
-            .await;
+        let _ = MetricsState::send_metric(
+            &self.state,
+            BehavioralMetricRequest::TelemetryInternalInitialize(data),
+        )
+        .await;
         let readable_result = internal_initialize_params
             .value
             .readable
@@ -511,13 +480,8 @@ impl MetricsServer for MetricsImpl {
             entity_id: media_load_start_params.entity_id,
         });
         trace!("metrics.media_load_start={:?}", media_load_start_message);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(media_load_start_message.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, media_load_start_message).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -531,13 +495,8 @@ impl MetricsServer for MetricsImpl {
             entity_id: media_play_params.entity_id,
         });
         trace!("metrics.media_play={:?}", media_play_message);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(media_play_message.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, media_play_message).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -551,13 +510,8 @@ impl MetricsServer for MetricsImpl {
             entity_id: media_playing_params.entity_id,
         });
         trace!("metrics.media_playing={:?}", media_playing);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(media_playing.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, media_playing).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -571,13 +525,8 @@ impl MetricsServer for MetricsImpl {
             entity_id: media_pause_params.entity_id,
         });
         trace!("metrics.media_pause={:?}", media_pause);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(media_pause.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, media_pause).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -591,13 +540,8 @@ impl MetricsServer for MetricsImpl {
             entity_id: media_waiting_params.entity_id,
         });
         trace!("metrics.media_waiting={:?}", media_waiting);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(media_waiting.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, media_waiting).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -613,13 +557,8 @@ impl MetricsServer for MetricsImpl {
             progress: Some(progress),
         });
         trace!("metrics.media_progress={:?}", media_progress);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(media_progress.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, media_progress).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -636,13 +575,8 @@ impl MetricsServer for MetricsImpl {
             target: Some(target),
         });
         trace!("metrics.media_seeking={:?}", media_seeking);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(media_seeking.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, media_seeking).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -658,13 +592,8 @@ impl MetricsServer for MetricsImpl {
             position: Some(position),
         });
         trace!("metrics.media_seeked={:?}", media_seeked);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(media_seeked.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, media_seeked).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -679,13 +608,8 @@ impl MetricsServer for MetricsImpl {
             rate: media_rate_changed_params.rate,
         });
         trace!("metrics.media_seeked={:?}", media_rate_change);
-        match self
-            .state
-            .get_client()
-            .send_extn_request(media_rate_change.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, media_rate_change).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -707,13 +631,8 @@ impl MetricsServer for MetricsImpl {
             "metrics.media_rendition_change={:?}",
             media_rendition_change
         );
-        match self
-            .state
-            .get_client()
-            .send_extn_request(media_rendition_change.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, media_rendition_change).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -728,13 +647,8 @@ impl MetricsServer for MetricsImpl {
         });
         trace!("metrics.media_ended={:?}", media_ended);
 
-        match self
-            .state
-            .get_client()
-            .send_extn_request(media_ended.clone())
-            .await
-        {
-            Ok(_) => Ok(true),
+        match MetricsState::send_metric(&self.state, media_ended).await {
+            Ok(sent) => Ok(sent),
             Err(_) => Err(rpc_err("parse error").into()),
         }
     }
@@ -0,0 +1,68 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use crate::{
+    firebolt::rpc::RippleRPCProvider,
+    service::data_reset::{DataReset, ResetReport},
+    state::platform_state::PlatformState,
+};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc, RpcModule};
+use ripple_sdk::{api::gateway::rpc_gateway_api::CallContext, async_trait::async_trait};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetRequest {
+    /// Resets only the data of this app when given, otherwise everything.
+    pub app_id: Option<String>,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[rpc(server)]
+pub trait Reset {
+    #[method(name = "reset.clear")]
+    async fn clear(&self, ctx: CallContext, request: ResetRequest) -> RpcResult<ResetReport>;
+}
+
+pub struct ResetImpl {
+    pub platform_state: PlatformState,
+}
+
+#[async_trait]
+impl ResetServer for ResetImpl {
+    async fn clear(&self, ctx: CallContext, request: ResetRequest) -> RpcResult<ResetReport> {
+        Ok(DataReset::reset(
+            &self.platform_state,
+            &ctx.app_id,
+            request.app_id,
+            request.dry_run,
+        )
+        .await)
+    }
+}
+
+pub struct ResetRPCProvider;
+
+impl RippleRPCProvider<ResetImpl> for ResetRPCProvider {
+    fn provide(state: PlatformState) -> RpcModule<ResetImpl> {
+        (ResetImpl {
+            platform_state: state,
+        })
+        .into_rpc()
+    }
+}
@@ -21,13 +21,19 @@ use jsonrpsee::{
     tracing::error,
     RpcModule,
 };
-use ripple_sdk::api::{
-    firebolt::fb_secure_storage::{
-        GetRequest, RemoveRequest, SecureStorageGetRequest, SecureStorageRemoveRequest,
-        SecureStorageRequest, SecureStorageResponse, SecureStorageSetRequest, SetRequest,
-        StorageSetOptions,
+use ripple_sdk::{
+    api::{
+        firebolt::fb_secure_storage::{
+            ClearAppRequest, ClearRequest, GetRequest, RemoveAppRequest, RemoveRequest,
+            SecureStorageClearRequest, SecureStorageGetRequest, SecureStorageRemoveRequest,
+            SecureStorageRequest, SecureStorageResponse, SecureStorageSetRequest, SetAppRequest,
+            SetRequest, StorageSetOptions,
+        },
+        gateway::rpc_gateway_api::CallContext,
+        session::AccountSession,
     },
-    gateway::rpc_gateway_api::CallContext,
+    extn::extn_client_message::ExtnResponse,
+    utils::error::RippleError,
 };
 
 use crate::{firebolt::rpc::RippleRPCProvider, state::platform_state::PlatformState};
@@ -40,6 +46,14 @@ pub trait SecureStorage {
     async fn set(&self, ctx: CallContext, request: SetRequest) -> RpcResult<()>;
     #[method(name = "securestorage.remove")]
     async fn remove(&self, ctx: CallContext, request: RemoveRequest) -> RpcResult<()>;
+    #[method(name = "securestorage.clear")]
+    async fn clear(&self, ctx: CallContext, request: ClearRequest) -> RpcResult<()>;
+    #[method(name = "securestorage.setForApp")]
+    async fn set_for_app(&self, ctx: CallContext, request: SetAppRequest) -> RpcResult<()>;
+    #[method(name = "securestorage.removeForApp")]
+    async fn remove_for_app(&self, ctx: CallContext, request: RemoveAppRequest) -> RpcResult<()>;
+    #[method(name = "securestorage.clearForApp")]
+    async fn clear_for_app(&self, ctx: CallContext, request: ClearAppRequest) -> RpcResult<()>;
 }
 pub struct SecureStorageImpl {
     pub state: PlatformState,
@@ -55,12 +69,14 @@ impl SecureStorageServer for SecureStorageImpl {
                 app_id: ctx.app_id,
                 scope: request.scope,
                 key: request.key,
-                distributor_session: self.state.session_state.get_account_session().unwrap(),
+                distributor_session: self.get_account_session()?,
             }))
             .await
         {
-            Ok(response) => match response.payload.extract().unwrap() {
-                SecureStorageResponse::Get(value) => Ok(value.value.unwrap_or(String::from(""))),
+            Ok(response) => match response.payload.extract() {
+                Some(SecureStorageResponse::Get(value)) => {
+                    Ok(value.value.unwrap_or(String::from("")))
+                }
                 _ => Err(jsonrpsee::core::Error::Custom(String::from(
                     "Secure Storage Response error response TBD",
                 ))),
@@ -75,47 +91,108 @@ impl SecureStorageServer for SecureStorageImpl {
     }
 
     async fn set(&self, ctx: CallContext, request: SetRequest) -> RpcResult<()> {
-        match self
-            .state
-            .get_client()
-            .send_extn_request(SecureStorageRequest::Set(SecureStorageSetRequest {
-                app_id: ctx.app_id,
-                value: request.value,
-                options: request.options.map(|op| StorageSetOptions { ttl: op.ttl }),
-                scope: request.scope,
-                key: request.key,
-                distributor_session: self.state.session_state.get_account_session().unwrap(),
-            }))
-            .await
-        {
-            Ok(_) => Ok(()),
-            Err(err) => {
-                error!("error={:?}", err);
-                Err(jsonrpsee::core::Error::Custom(
-                    "Error setting value".to_owned(),
-                ))
-            }
-        }
+        self.send(SecureStorageRequest::Set(SecureStorageSetRequest {
+            app_id: ctx.app_id,
+            value: request.value,
+            options: request.options.map(|op| StorageSetOptions { ttl: op.ttl }),
+            scope: request.scope,
+            key: request.key,
+            distributor_session: self.get_account_session()?,
+        }))
+        .await
     }
 
     async fn remove(&self, ctx: CallContext, request: RemoveRequest) -> RpcResult<()> {
-        match self
-            .state
-            .get_client()
-            .send_extn_request(SecureStorageRequest::Remove(SecureStorageRemoveRequest {
-                app_id: ctx.app_id,
+        self.send(SecureStorageRequest::Remove(SecureStorageRemoveRequest {
+            app_id: ctx.app_id,
+            scope: request.scope,
+            key: request.key,
+            distributor_session: self.get_account_session()?,
+        }))
+        .await
+    }
+
+    async fn clear(&self, ctx: CallContext, request: ClearRequest) -> RpcResult<()> {
+        self.send(SecureStorageRequest::Clear(SecureStorageClearRequest {
+            app_id: Some(ctx.app_id),
+            scope: Some(request.scope),
+            distributor_session: self.get_account_session()?,
+        }))
+        .await
+    }
+
+    async fn set_for_app(&self, _ctx: CallContext, request: SetAppRequest) -> RpcResult<()> {
+        self.send(SecureStorageRequest::SetForApp(SecureStorageSetRequest {
+            app_id: request.app_id,
+            value: request.value,
+            options: request.options.map(|op| StorageSetOptions { ttl: op.ttl }),
+            scope: request.scope,
+            key: request.key,
+            distributor_session: self.get_account_session()?,
+        }))
+        .await
+    }
+
+    async fn remove_for_app(&self, _ctx: CallContext, request: RemoveAppRequest) -> RpcResult<()> {
+        self.send(SecureStorageRequest::RemoveForApp(
+            SecureStorageRemoveRequest {
+                app_id: request.app_id,
                 scope: request.scope,
                 key: request.key,
-                distributor_session: self.state.session_state.get_account_session().unwrap(),
-            }))
-            .await
-        {
-            Ok(_) => Ok(()),
+                distributor_session: self.get_account_session()?,
+            },
+        ))
+        .await
+    }
+
+    async fn clear_for_app(&self, _ctx: CallContext, request: ClearAppRequest) -> RpcResult<()> {
+        self.send(SecureStorageRequest::Clear(SecureStorageClearRequest {
+            app_id: Some(request.app_id),
+            scope: Some(request.scope),
+            distributor_session: self.get_account_session()?,
+        }))
+        .await
+    }
+}
+
+impl SecureStorageImpl {
+    fn get_account_session(&self) -> RpcResult<AccountSession> {
+        self.state
+            .session_state
+            .get_account_session()
+            .ok_or_else(|| {
+                jsonrpsee::core::Error::Custom("Account session is not available".to_owned())
+            })
+    }
+
+    async fn send(&self, request: SecureStorageRequest) -> RpcResult<()> {
+        let (action, has_limits) = match request {
+            SecureStorageRequest::Set(_) | SecureStorageRequest::SetForApp(_) => ("setting", true),
+            SecureStorageRequest::Remove(_) | SecureStorageRequest::RemoveForApp(_) => {
+                ("removing", false)
+            }
+            _ => ("clearing", false),
+        };
+        match self.state.get_client().send_extn_request(request).await {
+            Ok(response) => match response.payload.extract() {
+                Some(ExtnResponse::Error(err)) => {
+                    error!("error={:?}", err);
+                    let message = match err {
+                        RippleError::InvalidInput if has_limits => {
+                            "Value exceeds the secure storage limits".to_owned()
+                        }
+                        _ => format!("Error {} value", action),
+                    };
+                    Err(jsonrpsee::core::Error::Custom(message))
+                }
+                _ => Ok(()),
+            },
             Err(err) => {
                 error!("error={:?}", err);
-                Err(jsonrpsee::core::Error::Custom(
-                    "Error setting value".to_owned(),
-                ))
+                Err(jsonrpsee::core::Error::Custom(format!(
+                    "Error {} value",
+                    action
+                )))
             }
         }
     }
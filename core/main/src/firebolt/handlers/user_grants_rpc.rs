@@ -19,10 +19,10 @@ use jsonrpsee::{core::RpcResult, proc_macros::rpc, RpcModule};
 use ripple_sdk::{
     api::{
         apps::{AppManagerResponse, AppMethod, AppRequest, AppResponse},
-        device::device_user_grants_data::{GrantEntry, GrantStateModify},
+        device::device_user_grants_data::{GrantAuditRecord, GrantEntry, GrantStateModify},
         firebolt::fb_user_grants::{
-            AppInfo, GetUserGrantsByAppRequest, GetUserGrantsByCapabilityRequest, GrantInfo,
-            GrantRequest,
+            AppInfo, GetUserGrantsByAppRequest, GetUserGrantsByCapabilityRequest, GrantHistoryInfo,
+            GrantHistoryRequest, GrantInfo, GrantRequest,
         },
         gateway::rpc_gateway_api::CallContext,
     },
@@ -32,7 +32,7 @@ use ripple_sdk::{
 
 use crate::{
     firebolt::rpc::RippleRPCProvider,
-    service::user_grants::GrantState,
+    service::{grant_audit::GrantAuditFilter, user_grants::GrantState},
     state::platform_state::PlatformState,
     utils::rpc_utils::{rpc_await_oneshot, rpc_err},
 };
@@ -64,6 +64,12 @@ pub trait UserGrants {
     fn usergrants_deny(&self, ctx: CallContext, request: GrantRequest) -> RpcResult<()>;
     #[method(name = "usergrants.clear")]
     fn usergrants_clear(&self, ctx: CallContext, request: GrantRequest) -> RpcResult<()>;
+    #[method(name = "usergrants.history")]
+    fn usergrants_history(
+        &self,
+        ctx: CallContext,
+        request: GrantHistoryRequest,
+    ) -> RpcResult<Vec<GrantHistoryInfo>>;
 }
 
 #[derive(Debug)]
@@ -139,6 +145,30 @@ impl UserGrantsImpl {
             })(),
         }
     }
+
+    fn parse_time(time: Option<String>) -> RpcResult<Option<u64>> {
+        match time {
+            Some(t) => match DateTime::parse_from_rfc3339(&t) {
+                Ok(date_time) => Ok(Some(date_time.timestamp_millis() as u64)),
+                Err(_) => Err(rpc_err(format!("Invalid date time {}", t))),
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn transform_audit_record(record: GrantAuditRecord) -> GrantHistoryInfo {
+        let time: DateTime<Utc> =
+            DateTime::from(SystemTime::UNIX_EPOCH + Duration::from_millis(record.timestamp));
+        GrantHistoryInfo {
+            time: time.to_rfc3339(),
+            app_id: record.app_id,
+            capability: record.capability,
+            role: record.role.as_string().to_owned(),
+            action: record.action,
+            lifespan: record.lifespan.map(|x| x.as_string().to_owned()),
+            step: record.step,
+        }
+    }
 }
 
 #[async_trait]
@@ -239,6 +269,27 @@ impl UserGrantsServer for UserGrantsImpl {
             Err(rpc_err("Unable to clear the capability"))
         }
     }
+
+    fn usergrants_history(
+        &self,
+        _ctx: CallContext,
+        request: GrantHistoryRequest,
+    ) -> RpcResult<Vec<GrantHistoryInfo>> {
+        let filter = GrantAuditFilter {
+            app_id: request.app_id,
+            capability: request.capability,
+            from: UserGrantsImpl::parse_time(request.from)?,
+            to: UserGrantsImpl::parse_time(request.to)?,
+        };
+        Ok(self
+            .platform_state
+            .cap_state
+            .grant_state
+            .get_audit_records(&filter)
+            .into_iter()
+            .map(UserGrantsImpl::transform_audit_record)
+            .collect())
+    }
 }
 
 pub struct UserGrantsRPCProvider;
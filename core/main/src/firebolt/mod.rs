@@ -26,6 +26,7 @@ pub mod handlers {
     pub mod capabilities_rpc;
     pub mod closed_captions_rpc;
     pub mod device_rpc;
+    pub mod diagnostics_rpc;
     pub mod discovery_rpc;
     pub mod keyboard_rpc;
     pub mod lcm_rpc;
@@ -36,6 +37,7 @@ pub mod handlers {
     pub mod pin_rpc;
     pub mod privacy_rpc;
     pub mod profile_rpc;
+    pub mod reset_rpc;
     pub mod second_screen_rpc;
     pub mod secure_storage_rpc;
     pub mod user_grants_rpc;
@@ -90,6 +90,8 @@ impl ExtnRequestProcessor for ConfigRequestProcessor {
                     lifecycle_policy: device_manifest.get_lifecycle_policy(),
                     retention_policy: device_manifest.get_retention_policy(),
                     app_library_state: state.clone().app_library_state,
+                    saved_dir: device_manifest.configuration.saved_dir.clone(),
+                    runtime_registry: device_manifest.get_runtime_registry(),
                 };
                 if let ExtnPayload::Response(r) = config.get_extn_payload() {
                     r
@@ -0,0 +1,125 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::collections::HashSet;
+
+use ripple_sdk::{
+    api::{
+        device::device_events::DeviceConditionEvent,
+        firebolt::fb_capabilities::{CapEvent, FireboltCap},
+    },
+    async_trait::async_trait,
+    extn::{
+        client::extn_processor::{
+            DefaultExtnStreamer, ExtnEventProcessor, ExtnStreamProcessor, ExtnStreamer,
+        },
+        extn_client_message::ExtnMessage,
+    },
+    log::debug,
+    tokio::sync::mpsc::Sender,
+};
+
+use crate::state::{cap::cap_state::CapState, platform_state::PlatformState};
+
+/// Processor to update capability availability from device conditions, configured in the
+/// `availabilityConditions` of the device manifest capabilities.
+#[derive(Debug)]
+pub struct DeviceConditionProcessor {
+    state: PlatformState,
+    streamer: DefaultExtnStreamer,
+}
+
+impl DeviceConditionProcessor {
+    pub fn new(state: PlatformState) -> DeviceConditionProcessor {
+        DeviceConditionProcessor {
+            state,
+            streamer: DefaultExtnStreamer::new(),
+        }
+    }
+}
+
+impl ExtnStreamProcessor for DeviceConditionProcessor {
+    type STATE = PlatformState;
+    type VALUE = DeviceConditionEvent;
+    fn get_state(&self) -> Self::STATE {
+        self.state.clone()
+    }
+
+    fn sender(&self) -> Sender<ExtnMessage> {
+        self.streamer.sender()
+    }
+
+    fn receiver(&mut self) -> ripple_sdk::tokio::sync::mpsc::Receiver<ExtnMessage> {
+        self.streamer.receiver()
+    }
+}
+
+#[async_trait]
+impl ExtnEventProcessor for DeviceConditionProcessor {
+    async fn process_event(
+        state: Self::STATE,
+        _msg: ExtnMessage,
+        extracted_message: Self::VALUE,
+    ) -> Option<bool> {
+        debug!("Device condition changed {:?}", extracted_message);
+        let conditions = state
+            .get_device_manifest()
+            .capabilities
+            .availability_conditions;
+        let caps = match conditions.get(&extracted_message.condition) {
+            Some(caps) => caps.clone(),
+            None => return None,
+        };
+        // A capability depending on several conditions stays unavailable until all of them hold
+        let blocked: HashSet<String> = if extracted_message.active {
+            conditions
+                .iter()
+                .filter(|(condition, _)| {
+                    **condition != extracted_message.condition
+                        && !state.cap_state.generic.is_condition_met(condition)
+                })
+                .flat_map(|(_, caps)| caps.iter().cloned())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        state
+            .cap_state
+            .generic
+            .set_condition(extracted_message.condition, extracted_message.active);
+        for cap in caps {
+            if blocked.contains(&cap) {
+                continue;
+            }
+            let cap = FireboltCap::Full(cap);
+            let available = state
+                .cap_state
+                .generic
+                .check_available(&vec![cap.clone()])
+                .is_ok();
+            if available != extracted_message.active {
+                let event = if extracted_message.active {
+                    CapEvent::OnAvailable
+                } else {
+                    CapEvent::OnUnavailable
+                };
+                CapState::emit(&state, event, cap, None).await;
+            }
+        }
+        None
+    }
+}
@@ -19,7 +19,7 @@ use ripple_sdk::{
     api::firebolt::{
         fb_capabilities::DenyReason,
         fb_keyboard::{KeyboardSessionRequest, KEYBOARD_PROVIDER_CAPABILITY},
-        provider::{ProviderRequestPayload, ProviderResponsePayload},
+        provider::ProviderRequestPayload,
     },
     async_trait::async_trait,
     extn::{
@@ -35,7 +35,9 @@ use ripple_sdk::{
 };
 
 use crate::{
-    service::apps::provider_broker::{ProviderBroker, ProviderBrokerRequest},
+    service::apps::provider_broker::{
+        ProviderBroker, ProviderBrokerRequest, ProviderBrokerResponse,
+    },
     state::platform_state::PlatformState,
 };
 
@@ -84,7 +86,7 @@ impl ExtnRequestProcessor for KeyboardProcessor {
         extracted_message: Self::VALUE,
     ) -> bool {
         let method = String::from(extracted_message._type.to_provider_method());
-        let (session_tx, session_rx) = oneshot::channel::<ProviderResponsePayload>();
+        let (session_tx, session_rx) = oneshot::channel::<ProviderBrokerResponse>();
         let pr_msg = ProviderBrokerRequest {
             capability: KEYBOARD_PROVIDER_CAPABILITY.to_string(),
             method: method,
@@ -95,7 +97,7 @@ impl ExtnRequestProcessor for KeyboardProcessor {
         };
         ProviderBroker::invoke_method(&state, pr_msg).await;
         match session_rx.await {
-            Ok(result) => match result.as_keyboard_result() {
+            Ok(Ok(result)) => match result.as_keyboard_result() {
                 Some(keyboard_response) => {
                     if let Ok(_) = Self::respond(
                         state.get_client().get_extn_client(),
@@ -109,7 +111,7 @@ impl ExtnRequestProcessor for KeyboardProcessor {
                 }
                 None => {}
             },
-            Err(_) => {}
+            _ => {}
         }
         Self::handle_error(
             state.get_client().get_extn_client(),
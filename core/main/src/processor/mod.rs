@@ -19,10 +19,12 @@ pub mod account_link_processor;
 pub mod app_events_processor;
 pub mod authorized_info_processor;
 pub mod config_processor;
+pub mod device_condition_processor;
 pub mod exn_status_processor;
 pub mod keyboard_processor;
 pub mod lifecycle_management_processor;
 pub mod pin_processor;
+pub mod power_state_processor;
 pub mod rpc_gateway_processor;
 pub mod settings_processor;
 pub mod storage;
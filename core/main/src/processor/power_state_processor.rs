@@ -0,0 +1,78 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use ripple_sdk::{
+    api::device::device_request::{PowerState, SystemPowerState},
+    async_trait::async_trait,
+    extn::{
+        client::extn_processor::{
+            DefaultExtnStreamer, ExtnEventProcessor, ExtnStreamProcessor, ExtnStreamer,
+        },
+        extn_client_message::ExtnMessage,
+    },
+    log::debug,
+    tokio::sync::mpsc::Sender,
+};
+
+use crate::{service::user_grants::GrantState, state::platform_state::PlatformState};
+
+/// Processor to handle power state changes from the device channel.
+#[derive(Debug)]
+pub struct PowerStateProcessor {
+    state: PlatformState,
+    streamer: DefaultExtnStreamer,
+}
+
+impl PowerStateProcessor {
+    pub fn new(state: PlatformState) -> PowerStateProcessor {
+        PowerStateProcessor {
+            state,
+            streamer: DefaultExtnStreamer::new(),
+        }
+    }
+}
+
+impl ExtnStreamProcessor for PowerStateProcessor {
+    type STATE = PlatformState;
+    type VALUE = SystemPowerState;
+    fn get_state(&self) -> Self::STATE {
+        self.state.clone()
+    }
+
+    fn sender(&self) -> Sender<ExtnMessage> {
+        self.streamer.sender()
+    }
+
+    fn receiver(&mut self) -> ripple_sdk::tokio::sync::mpsc::Receiver<ExtnMessage> {
+        self.streamer.receiver()
+    }
+}
+
+#[async_trait]
+impl ExtnEventProcessor for PowerStateProcessor {
+    async fn process_event(
+        state: Self::STATE,
+        _msg: ExtnMessage,
+        extracted_message: Self::VALUE,
+    ) -> Option<bool> {
+        debug!("Power state changed {:?}", extracted_message);
+        if extracted_message.power_state != PowerState::On {
+            GrantState::clear_power_active_grants(&state).await;
+        }
+        None
+    }
+}
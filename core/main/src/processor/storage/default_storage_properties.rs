@@ -24,6 +24,7 @@ use ripple_sdk::{
         NAMESPACE_LOCALIZATION, NAMESPACE_VOICE_GUIDANCE,
     },
     log::debug,
+    serde_json::{json, Value},
 };
 
 use crate::state::platform_state::PlatformState;
@@ -232,4 +233,23 @@ impl DefaultStorageProperties {
             Err(())
         }
     }
+
+    /// Default value of the property whatever its type, if the manifest has one.
+    pub fn get_value(
+        state: &PlatformState,
+        namespace: &String,
+        key: &'static str,
+    ) -> Option<Value> {
+        if let Ok(value) = Self::get_bool(state, namespace, key) {
+            Some(json!(value))
+        } else if let Ok(value) = Self::get_string(state, namespace, key) {
+            Some(json!(value))
+        } else if let Ok(value) = Self::get_number_as_u32(state, namespace, key) {
+            Some(json!(value))
+        } else if let Ok(value) = Self::get_number_as_f32(state, namespace, key) {
+            Some(json!(value))
+        } else {
+            None
+        }
+    }
 }
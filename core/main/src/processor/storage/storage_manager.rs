@@ -19,7 +19,8 @@ use jsonrpsee::{core::RpcResult, types::error::CallError};
 use ripple_sdk::{
     api::{
         device::device_peristence::{
-            DevicePersistenceRequest, GetStorageProperty, SetStorageProperty, StorageData,
+            DeleteStorageProperty, DevicePersistenceRequest, GetStorageProperty,
+            SetStorageProperty, StorageData,
         },
         firebolt::fb_capabilities::CAPABILITY_NOT_AVAILABLE,
         storage_property::StorageProperty,
@@ -37,7 +38,7 @@ use crate::{
         storage_to_u32_rpc_result,
     },
     service::apps::app_events::AppEvents,
-    state::platform_state::PlatformState,
+    state::{metrics_state::MetricsState, platform_state::PlatformState},
 };
 
 use super::default_storage_properties::DefaultStorageProperties;
@@ -119,7 +120,7 @@ impl StorageManager {
         context: Option<Value>,
     ) -> RpcResult<()> {
         let data = property.as_data();
-        if let Err(_) = StorageManager::set_in_namespace(
+        match StorageManager::set_in_namespace(
             state,
             data.namespace.to_string(),
             data.key.to_string(),
@@ -129,11 +130,21 @@ impl StorageManager {
         )
         .await
         {
-            return Err(StorageManager::get_firebolt_error(&property));
+            Ok(StorageManagerResponse::Ok(_)) => StorageManager::on_changed(state, &property),
+            Ok(_) => {}
+            Err(_) => return Err(StorageManager::get_firebolt_error(&property)),
         }
         Ok(())
     }
 
+    /// Refreshes the state derived from a property after its value changed.
+    fn on_changed(state: &PlatformState, property: &StorageProperty) {
+        if let StorageProperty::Language = property {
+            let state = state.clone();
+            tokio::spawn(async move { MetricsState::refresh_context(&state).await });
+        }
+    }
+
     pub async fn get_number_as_u32(
         state: &PlatformState,
         property: StorageProperty,
@@ -272,17 +283,7 @@ impl StorageManager {
         {
             Ok(_) => {
                 if let Some(events) = event_names {
-                    let val = Value::from(value.clone());
-                    for event in events.iter() {
-                        let state_for_event = state.clone();
-                        let result = val.clone();
-                        let ctx = context.clone();
-                        let evt = String::from(*event);
-                        tokio::spawn(async move {
-                            AppEvents::emit_with_context(&state_for_event, &evt, &result, ctx)
-                                .await;
-                        });
-                    }
+                    StorageManager::emit_changes(state, events, value, context);
                 }
                 Ok(StorageManagerResponse::Ok(()))
             }
@@ -290,6 +291,106 @@ impl StorageManager {
         }
     }
 
+    fn emit_changes(
+        state: &PlatformState,
+        event_names: &'static [&'static str],
+        value: Value,
+        context: Option<Value>,
+    ) {
+        for event in event_names.iter() {
+            let state_for_event = state.clone();
+            let result = value.clone();
+            let ctx = context.clone();
+            let evt = String::from(*event);
+            tokio::spawn(async move {
+                AppEvents::emit_with_context(&state_for_event, &evt, &result, ctx).await;
+            });
+        }
+    }
+
+    /// Deletes the stored value of the property, listeners are notified of the default value
+    /// which it reads from then on.
+    pub async fn reset(
+        state: &PlatformState,
+        property: StorageProperty,
+    ) -> Result<StorageManagerResponse<()>, StorageManagerError> {
+        let data = property.as_data();
+        debug!("reset: namespace={}, key={}", data.namespace, data.key);
+        let request = DevicePersistenceRequest::Delete(DeleteStorageProperty {
+            namespace: data.namespace.to_string(),
+            key: data.key.to_string(),
+        });
+        StorageManager::send_write(state, request).await?;
+        if StorageManager::flush(state).await.is_err() {
+            debug!("Storage could not be flushed after the reset");
+        }
+        if let (Some(events), Some(value)) = (
+            data.event_names,
+            DefaultStorageProperties::get_value(state, &data.namespace.to_string(), data.key),
+        ) {
+            StorageManager::emit_changes(state, events, value, None);
+        }
+        StorageManager::on_changed(state, &property);
+        Ok(StorageManagerResponse::Ok(()))
+    }
+
+    /// Deletes every stored value of the namespace. Unlike [StorageManager::reset] no change
+    /// events are emitted as the namespace may hold keys which aren't storage properties.
+    pub async fn reset_namespace(
+        state: &PlatformState,
+        namespace: String,
+    ) -> Result<StorageManagerResponse<()>, StorageManagerError> {
+        debug!("reset_namespace: namespace={}", namespace);
+        if let Ok(keys) = StorageManager::get_keys(state, namespace.clone()).await {
+            if keys.is_empty() {
+                return Ok(StorageManagerResponse::NoChange(()));
+            }
+        }
+        StorageManager::send_write(state, DevicePersistenceRequest::DeleteNamespace(namespace))
+            .await?;
+        if StorageManager::flush(state).await.is_err() {
+            debug!("Storage could not be flushed after the reset");
+        }
+        Ok(StorageManagerResponse::Ok(()))
+    }
+
+    pub async fn get_keys(
+        state: &PlatformState,
+        namespace: String,
+    ) -> Result<Vec<String>, StorageManagerError> {
+        StorageManager::send_list(state, DevicePersistenceRequest::GetKeys(namespace)).await
+    }
+
+    pub async fn flush(state: &PlatformState) -> Result<(), StorageManagerError> {
+        StorageManager::send_write(state, DevicePersistenceRequest::Flush).await
+    }
+
+    async fn send_write(
+        state: &PlatformState,
+        request: DevicePersistenceRequest,
+    ) -> Result<(), StorageManagerError> {
+        match state.get_client().send_extn_request(request).await {
+            Ok(msg) => match msg.payload.extract() {
+                Some(ExtnResponse::Boolean(true)) => Ok(()),
+                _ => Err(StorageManagerError::WriteError),
+            },
+            Err(_) => Err(StorageManagerError::WriteError),
+        }
+    }
+
+    async fn send_list(
+        state: &PlatformState,
+        request: DevicePersistenceRequest,
+    ) -> Result<Vec<String>, StorageManagerError> {
+        match state.get_client().send_extn_request(request).await {
+            Ok(msg) => match msg.payload.extract() {
+                Some(ExtnResponse::List(list)) => Ok(list),
+                _ => Err(StorageManagerError::NotFound),
+            },
+            Err(_) => Err(StorageManagerError::NotFound),
+        }
+    }
+
     /*
     Used internally or when a custom namespace is required
      */
@@ -96,6 +96,20 @@ impl ExtnRequestProcessor for StorageManagerProcessor {
                         .is_ok()
                 }
             }
+            StorageManagerRequest::Reset(property) => {
+                let result = StorageManager::reset(&state, property).await.is_ok();
+                Self::respond(client, msg, ExtnResponse::Boolean(result))
+                    .await
+                    .is_ok()
+            }
+            StorageManagerRequest::ResetNamespace(namespace) => {
+                let result = StorageManager::reset_namespace(&state, namespace)
+                    .await
+                    .is_ok();
+                Self::respond(client, msg, ExtnResponse::Boolean(result))
+                    .await
+                    .is_ok()
+            }
         }
     }
 }
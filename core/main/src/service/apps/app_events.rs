@@ -24,21 +24,24 @@ use ripple_sdk::{
         apps::{AppEventRequest, EffectiveTransport},
         firebolt::fb_general::ListenRequest,
         gateway::rpc_gateway_api::{ApiMessage, CallContext},
+        manifest::device_manifest::{EventDeliveryConfig, StickyEventsConfig},
         protocol::BridgeProtocolRequest,
     },
-    log::error,
+    log::{debug, error},
     serde_json::{json, Value},
-    tokio::sync::mpsc,
-    utils::channel_utils::mpsc_send_and_log,
+    tokio::{self, sync::mpsc},
 };
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     sync::{Arc, RwLock},
+    time::Instant,
 };
 
 use crate::state::platform_state::PlatformState;
 
+use super::event_delivery::EventDelivery;
+
 #[derive(Debug)]
 pub struct AppEventDecorationError {}
 
@@ -73,6 +76,121 @@ pub struct AppEvents {}
 #[derive(Clone, Default)]
 pub struct AppEventsState {
     pub listeners: Arc<RwLock<HashMap<String, HashMap<Option<String>, Vec<EventListener>>>>>,
+    sticky_events: Arc<RwLock<StickyEvents>>,
+    pub delivery: EventDelivery,
+}
+
+impl AppEventsState {
+    pub fn new(
+        sticky_config: StickyEventsConfig,
+        delivery_config: EventDeliveryConfig,
+    ) -> AppEventsState {
+        AppEventsState {
+            listeners: Arc::default(),
+            sticky_events: Arc::new(RwLock::new(StickyEvents {
+                config: sticky_config,
+                ..Default::default()
+            })),
+            delivery: EventDelivery::new(delivery_config),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct StickyEvent {
+    // None for events emitted to all apps
+    app_id: Option<String>,
+    context: Option<Value>,
+    result: Value,
+    size: usize,
+    seq: u64,
+}
+
+/// Last values of the events configured as sticky, replayed to listeners added later.
+#[derive(Debug, Default)]
+struct StickyEvents {
+    config: StickyEventsConfig,
+    events: HashMap<String, VecDeque<StickyEvent>>,
+    size: usize,
+    seq: u64,
+}
+
+impl StickyEvents {
+    fn retain(
+        &mut self,
+        event_name: &str,
+        app_id: Option<String>,
+        context: Option<Value>,
+        result: &Value,
+    ) {
+        let history = match self.config.events.get(event_name) {
+            Some(history) if *history > 0 => *history,
+            _ => return,
+        };
+        let max_size = self.config.max_size_kb as usize * 1024;
+        let size = result.to_string().len();
+        if size > max_size {
+            return;
+        }
+        self.seq += 1;
+        let retained = self.events.entry(event_name.to_owned()).or_default();
+        let is_same = |e: &StickyEvent| e.app_id == app_id && e.context == context;
+        if retained.iter().filter(|e| is_same(e)).count() >= history {
+            if let Some(index) = retained.iter().position(is_same) {
+                if let Some(dropped) = retained.remove(index) {
+                    self.size -= dropped.size;
+                }
+            }
+        }
+        retained.push_back(StickyEvent {
+            app_id,
+            context,
+            result: result.clone(),
+            size,
+            seq: self.seq,
+        });
+        self.size += size;
+
+        while self.size > max_size {
+            let oldest = self
+                .events
+                .iter()
+                .filter_map(|(name, retained)| retained.front().map(|e| (e.seq, name.clone())))
+                .min();
+            match oldest.and_then(|(_, name)| self.events.get_mut(&name)) {
+                Some(retained) => {
+                    if let Some(dropped) = retained.pop_front() {
+                        self.size -= dropped.size;
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Retained values visible to a listener of the app, oldest first. Listeners without
+    /// an event context also get the values emitted with one.
+    fn replay(&self, event_name: &str, app_id: &str, context: &Option<String>) -> Vec<StickyEvent> {
+        match self.events.get(event_name) {
+            Some(retained) => retained
+                .iter()
+                .filter(|e| e.app_id.is_none() || e.app_id.as_deref() == Some(app_id))
+                .filter(|e| {
+                    context.is_none() || e.context.as_ref().map(|c| c.to_string()) == *context
+                })
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Sequence of the latest value a listener of the app would be replayed.
+    fn latest_seq(&self, event_name: &str, app_id: &str, context: &Option<String>) -> Option<u64> {
+        self.replay(event_name, app_id, context)
+            .iter()
+            .map(|e| e.seq)
+            .max()
+    }
 }
 
 impl std::fmt::Debug for AppEventsState {
@@ -243,17 +361,36 @@ impl AppEvents {
         if listen_request.listen {
             let event_listeners = AppEvents::get_or_create_listener_vec(
                 &mut listeners,
-                event_name,
+                event_name.clone(),
                 event_ctx_string.clone(),
             );
             //The last listener wins if there is already a listener exists with same session id
             AppEvents::remove_session_from_events(event_listeners, &call_ctx.session_id);
-            event_listeners.push(EventListener {
+            let listener = EventListener {
                 call_ctx: call_ctx,
                 session_tx: session.get_sender(),
                 transport: session.get_transport(),
                 decorator: decorator,
-            });
+            };
+            event_listeners.push(listener.clone());
+            let replay = app_events_state.sticky_events.read().unwrap().replay(
+                &event_name,
+                &listener.call_ctx.app_id,
+                &event_ctx_string,
+            );
+            if !replay.is_empty() {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    AppEvents::replay_sticky_events(
+                        &state,
+                        &listener,
+                        &event_name,
+                        event_ctx_string,
+                        replay,
+                    )
+                    .await
+                });
+            }
         } else if let Some(entry) = listeners.get_mut(&event_name) {
             if let Some(event_listeners) = entry.get_mut(&event_ctx_string) {
                 AppEvents::remove_session_from_events(event_listeners, &call_ctx.session_id);
@@ -261,6 +398,55 @@ impl AppEvents {
         }
     }
 
+    /// Sends the retained values to a listener which is already registered. Values emitted
+    /// meanwhile are delivered live, the replay stops once a newer value has been retained so
+    /// it never overwrites them with older ones.
+    async fn replay_sticky_events(
+        state: &PlatformState,
+        listener: &EventListener,
+        event_name: &str,
+        event_context: Option<String>,
+        replay: Vec<StickyEvent>,
+    ) {
+        let last_seq = replay.last().map(|e| e.seq).unwrap_or_default();
+        let has_newer = || {
+            let latest = state
+                .app_events_state
+                .sticky_events
+                .read()
+                .unwrap()
+                .latest_seq(event_name, &listener.call_ctx.app_id, &event_context);
+            latest.unwrap_or_default() > last_seq
+        };
+        for event in replay {
+            // Same shape as emit_with_context delivers to the listener
+            let data = if event_context.is_some() {
+                event.result
+            } else {
+                let decorated_res = match listener.decorate(state, event_name, &event.result).await
+                {
+                    Ok(res) => res,
+                    Err(_) => {
+                        error!("could not generate event for '{}'", event_name);
+                        continue;
+                    }
+                };
+                match event.context {
+                    Some(context) => json!({
+                        "context": context,
+                        "value"  : &decorated_res,
+                    }),
+                    None => decorated_res,
+                }
+            };
+            if has_newer() {
+                debug!("Newer value of {} emitted, ending the replay", event_name);
+                return;
+            }
+            AppEvents::send_event(state, listener, &data).await;
+        }
+    }
+
     pub async fn send_event(state: &PlatformState, listener: &EventListener, data: &Value) {
         let protocol = listener.call_ctx.protocol.clone();
         let event = Response {
@@ -274,23 +460,38 @@ impl AppEvents {
             listener.call_ctx.request_id.clone(),
         );
 
+        let delivery = &state.app_events_state.delivery;
         match listener.transport.clone() {
             EffectiveTransport::Websocket => {
                 if let Some(session_tx) = listener.session_tx.clone() {
-                    mpsc_send_and_log(&session_tx, api_message, "GatewayResponse").await;
+                    delivery
+                        .send(
+                            &state.session_state,
+                            &listener.call_ctx,
+                            session_tx,
+                            api_message,
+                        )
+                        .await;
                 } else {
                     error!("JsonRPC sender missing");
                 }
             }
             EffectiveTransport::Bridge(id) => {
+                delivery.count_emitted(&listener.call_ctx);
                 if state.supports_bridge() {
                     let client = state.get_client();
                     let request = BridgeProtocolRequest::Send(id, api_message);
-                    if let Err(e) = client.send_extn_request(request).await {
-                        error!("Error sending event to bridge {:?}", e);
+                    let sent_at = Instant::now();
+                    match client.send_extn_request(request).await {
+                        Ok(_) => delivery.count_delivered(&listener.call_ctx, sent_at.elapsed()),
+                        Err(e) => {
+                            error!("Error sending event to bridge {:?}", e);
+                            delivery.count_dropped(&listener.call_ctx);
+                        }
                     }
                 } else {
                     error!("Bridge not supported");
+                    delivery.count_dropped(&listener.call_ctx);
                 }
             }
         }
@@ -340,6 +541,12 @@ impl AppEvents {
         result: &Value,
         context: Option<Value>,
     ) {
+        state
+            .app_events_state
+            .sticky_events
+            .write()
+            .unwrap()
+            .retain(event_name, None, context.clone(), result);
         // Notify all the default listners by providing the context data as part of the result when context
         // is present. Otherwise event result without context.
         let listeners = AppEvents::get_listeners(&state.app_events_state, event_name, None);
@@ -384,6 +591,12 @@ impl AppEvents {
         event_name: &str,
         result: &Value,
     ) {
+        state
+            .app_events_state
+            .sticky_events
+            .write()
+            .unwrap()
+            .retain(event_name, Some(app_id.clone()), None, result);
         let listeners_vec = AppEvents::get_listeners(&state.app_events_state, event_name, None)
             .into_iter()
             .filter(|listener| listener.call_ctx.app_id.eq(&app_id))
@@ -419,17 +632,92 @@ impl AppEvents {
 
     pub fn remove_session(state: &PlatformState, session_id: String) {
         state.session_state.clear_session(&session_id);
-        let mut listeners = state.app_events_state.listeners.write().unwrap();
-        let all_events = listeners.keys().cloned().collect::<Vec<String>>();
-        for event_name in all_events {
-            if let Some(ctx_map) = listeners.get_mut(&event_name) {
-                let all_contexts = ctx_map.keys().cloned().collect::<Vec<Option<String>>>();
-                for context in all_contexts {
-                    if let Some(event_listener) = ctx_map.get_mut(&context) {
-                        AppEvents::remove_session_from_events(event_listener, &session_id);
+        {
+            let mut listeners = state.app_events_state.listeners.write().unwrap();
+            let all_events = listeners.keys().cloned().collect::<Vec<String>>();
+            for event_name in all_events {
+                if let Some(ctx_map) = listeners.get_mut(&event_name) {
+                    let all_contexts = ctx_map.keys().cloned().collect::<Vec<Option<String>>>();
+                    for context in all_contexts {
+                        if let Some(event_listener) = ctx_map.get_mut(&context) {
+                            AppEvents::remove_session_from_events(event_listener, &session_id);
+                        }
                     }
                 }
             }
         }
+        // after the listeners are gone so no event can start a new outbox for the session
+        state.app_events_state.delivery.remove_session(&session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sticky_events(history: usize, max_size_kb: u64) -> StickyEvents {
+        StickyEvents {
+            config: StickyEventsConfig {
+                events: HashMap::from([("test.onEvent".to_owned(), history)]),
+                max_size_kb,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn values(replay: Vec<StickyEvent>) -> Vec<Value> {
+        replay.into_iter().map(|e| e.result).collect()
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut sticky = sticky_events(2, 1);
+        for i in 0..3 {
+            sticky.retain("test.onEvent", None, None, &json!(i));
+        }
+        sticky.retain("test.onOther", None, None, &json!("not sticky"));
+
+        assert_eq!(
+            values(sticky.replay("test.onEvent", "app", &None)),
+            vec![json!(1), json!(2)]
+        );
+        assert!(sticky.replay("test.onOther", "app", &None).is_empty());
+    }
+
+    #[test]
+    fn test_size_bound() {
+        let mut sticky = sticky_events(10, 1);
+        let large = json!("v".repeat(400));
+        for _ in 0..3 {
+            sticky.retain("test.onEvent", None, None, &large);
+        }
+        assert_eq!(sticky.replay("test.onEvent", "app", &None).len(), 2);
+        assert!(sticky.size <= 1024);
+
+        sticky.retain("test.onEvent", None, None, &json!("v".repeat(2048)));
+        assert_eq!(sticky.replay("test.onEvent", "app", &None).len(), 2);
+    }
+
+    #[test]
+    fn test_context_and_app_filtering() {
+        let mut sticky = sticky_events(1, 1);
+        let context = json!({"appId": "a"});
+        sticky.retain("test.onEvent", None, Some(context.clone()), &json!("a"));
+        sticky.retain("test.onEvent", None, None, &json!("all"));
+        sticky.retain("test.onEvent", Some("app".into()), None, &json!("app"));
+
+        assert_eq!(
+            values(sticky.replay("test.onEvent", "app", &Some(context.to_string()))),
+            vec![json!("a")]
+        );
+        assert_eq!(
+            values(sticky.replay("test.onEvent", "other", &None)),
+            vec![json!("a"), json!("all")]
+        );
+        assert_eq!(
+            values(sticky.replay("test.onEvent", "app", &None)),
+            vec![json!("a"), json!("all"), json!("app")]
+        );
+        assert_eq!(sticky.latest_seq("test.onEvent", "other", &None), Some(2));
     }
 }
@@ -26,9 +26,10 @@ use ripple_sdk::{
             AppError, AppManagerResponse, AppMethod, AppSession, EffectiveTransport, StateChange,
         },
         firebolt::{
-            fb_discovery::DISCOVERY_EVENT_ON_NAVIGATE_TO, fb_lifecycle::LifecycleState,
-            fb_secondscreen::SECOND_SCREEN_EVENT_ON_LAUNCH_REQUEST,
+            fb_capabilities::FireboltCap, fb_discovery::DISCOVERY_EVENT_ON_NAVIGATE_TO,
+            fb_lifecycle::LifecycleState, fb_secondscreen::SECOND_SCREEN_EVENT_ON_LAUNCH_REQUEST,
         },
+        manifest::app_library::AppLibrary,
     },
     log::{debug, error, warn},
     serde_json::{self},
@@ -86,6 +87,17 @@ impl AppManagerState {
         self.apps.read().unwrap().contains_key(app_id)
     }
 
+    pub fn is_foreground(&self, app_id: &str) -> bool {
+        matches!(
+            self.apps.read().unwrap().get(app_id),
+            Some(app) if app.state == LifecycleState::Foreground
+        )
+    }
+
+    pub fn get_app_ids(&self) -> Vec<String> {
+        self.apps.read().unwrap().keys().cloned().collect()
+    }
+
     fn set_session(&self, app_id: &str, session: AppSession) {
         let mut apps = self.apps.write().unwrap();
         if let Some(app) = apps.get_mut(app_id) {
@@ -246,6 +258,42 @@ impl DelegatedLauncherHandler {
         }
     }
 
+    /// Checks the required capabilities used by the app against the capabilities supported by
+    /// the device. Apps without an embedded manifest are not checked.
+    fn check_required_capabilities(&self, app_id: &str) -> Result<(), AppError> {
+        let manifest =
+            match AppLibrary::get_manifest(&self.platform_state.app_library_state, app_id) {
+                Some(manifest) => manifest,
+                None => return Ok(()),
+            };
+        let required = FireboltCap::from_vec_string(manifest.capabilities.used.required);
+        if let Err(e) = self
+            .platform_state
+            .cap_state
+            .generic
+            .check_supported(&required)
+        {
+            let caps: Vec<String> = e.caps.iter().map(|cap| cap.as_str()).collect();
+            if self
+                .platform_state
+                .get_device_manifest()
+                .capabilities
+                .enforce_required
+            {
+                error!(
+                    "check_required_capabilities: app_id={} Unsupported capabilities {:?}",
+                    app_id, caps
+                );
+                return Err(AppError::NotSupported);
+            }
+            warn!(
+                "check_required_capabilities: app_id={} Unsupported capabilities {:?}",
+                app_id, caps
+            );
+        }
+        Ok(())
+    }
+
     async fn start_session(&mut self, session: AppSession) -> Result<AppManagerResponse, AppError> {
         let app_id = session.app.id.clone();
         let transport = session.get_transport();
@@ -293,6 +341,7 @@ impl DelegatedLauncherHandler {
                 return Err(AppError::AppNotReady);
             }
         } else {
+            self.check_required_capabilities(&app_id)?;
             session_id = Uuid::new_v4().to_string();
             let platform_state_c = self.platform_state.clone();
             let app_id_c = app_id.clone();
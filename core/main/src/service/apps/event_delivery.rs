@@ -0,0 +1,478 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+use ripple_sdk::{
+    api::{
+        gateway::rpc_gateway_api::{ApiMessage, CallContext},
+        manifest::device_manifest::{EventDeliveryConfig, SlowConsumerPolicy},
+    },
+    log::{error, warn},
+    tokio::{
+        self,
+        sync::{mpsc, Notify},
+    },
+};
+use serde::Serialize;
+
+use crate::state::session_state::SessionState;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventCounters {
+    pub emitted: u64,
+    pub delivered: u64,
+    pub dropped: u64,
+    pub avg_latency_ms: u64,
+    pub max_latency_ms: u64,
+    #[serde(skip)]
+    total_latency_ms: u64,
+}
+
+impl EventCounters {
+    fn add_delivery(&mut self, latency: Duration) {
+        let latency_ms = latency.as_millis() as u64;
+        self.delivered += 1;
+        self.total_latency_ms += latency_ms;
+        self.avg_latency_ms = self.total_latency_ms / self.delivered;
+        self.max_latency_ms = self.max_latency_ms.max(latency_ms);
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEventMetrics {
+    pub app_id: String,
+    pub totals: EventCounters,
+    pub events: HashMap<String, EventCounters>,
+}
+
+/// Delivery counters of the open sessions and of each event since Ripple started. Sessions
+/// are keyed by app id and the order they were seen in, session ids are credentials and are
+/// never reported.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventMetrics {
+    pub sessions: HashMap<String, SessionEventMetrics>,
+    pub events: HashMap<String, EventCounters>,
+}
+
+/// Counters of a session along with the index it's reported under.
+#[derive(Debug, Default)]
+struct SessionCounters {
+    index: u64,
+    metrics: SessionEventMetrics,
+}
+
+#[derive(Debug, Default)]
+struct EventMetricsState {
+    sessions: HashMap<String, SessionCounters>,
+    events: HashMap<String, EventCounters>,
+    next_index: u64,
+}
+
+impl EventMetricsState {
+    fn update(&mut self, ctx: &CallContext, update: impl Fn(&mut EventCounters)) {
+        let next_index = &mut self.next_index;
+        let session = self
+            .sessions
+            .entry(ctx.session_id.clone())
+            .or_insert_with(|| {
+                *next_index += 1;
+                SessionCounters {
+                    index: *next_index,
+                    metrics: SessionEventMetrics {
+                        app_id: ctx.app_id.clone(),
+                        ..Default::default()
+                    },
+                }
+            });
+        update(&mut session.metrics.totals);
+        update(
+            session
+                .metrics
+                .events
+                .entry(ctx.method.clone())
+                .or_default(),
+        );
+        update(self.events.entry(ctx.method.clone()).or_default());
+    }
+
+    fn report(&self) -> EventMetrics {
+        EventMetrics {
+            sessions: self
+                .sessions
+                .values()
+                .map(|session| {
+                    (
+                        format!("{}#{}", session.metrics.app_id, session.index),
+                        session.metrics.clone(),
+                    )
+                })
+                .collect(),
+            events: self.events.clone(),
+        }
+    }
+}
+
+struct PendingEvent {
+    ctx: CallContext,
+    message: ApiMessage,
+    queued_at: Instant,
+}
+
+/// Events waiting to be written to the channel of a websocket session.
+#[derive(Default)]
+struct Outbox {
+    pending: Mutex<VecDeque<PendingEvent>>,
+    notify: Notify,
+    closing: Notify,
+}
+
+#[derive(Clone, Default)]
+pub struct EventDelivery {
+    config: EventDeliveryConfig,
+    outboxes: Arc<RwLock<HashMap<String, Arc<Outbox>>>>,
+    metrics: Arc<RwLock<EventMetricsState>>,
+}
+
+impl EventDelivery {
+    pub fn new(config: EventDeliveryConfig) -> EventDelivery {
+        EventDelivery {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn get_metrics(&self) -> EventMetrics {
+        self.metrics.read().unwrap().report()
+    }
+
+    pub fn count_emitted(&self, ctx: &CallContext) {
+        self.metrics
+            .write()
+            .unwrap()
+            .update(ctx, |c| c.emitted += 1);
+    }
+
+    pub fn count_delivered(&self, ctx: &CallContext, latency: Duration) {
+        self.metrics
+            .write()
+            .unwrap()
+            .update(ctx, |c| c.add_delivery(latency));
+    }
+
+    pub fn count_dropped(&self, ctx: &CallContext) {
+        self.metrics
+            .write()
+            .unwrap()
+            .update(ctx, |c| c.dropped += 1);
+    }
+
+    /// Sends an event to a websocket session. Unless the slow consumer policy is
+    /// [SlowConsumerPolicy::Backpressure] the event is queued and the policy applies when the
+    /// session already has the maximum of events pending.
+    pub async fn send(
+        &self,
+        session_state: &SessionState,
+        ctx: &CallContext,
+        session_tx: mpsc::Sender<ApiMessage>,
+        message: ApiMessage,
+    ) {
+        self.count_emitted(ctx);
+        if self.config.slow_consumer_policy == SlowConsumerPolicy::Backpressure {
+            let sent_at = Instant::now();
+            match session_tx.send(message).await {
+                Ok(_) => self.count_delivered(ctx, sent_at.elapsed()),
+                Err(e) => {
+                    error!("Error sending event to {} {:?}", ctx.app_id, e);
+                    self.count_dropped(ctx);
+                }
+            }
+            return;
+        }
+        self.enqueue(session_state, ctx, session_tx, message)
+    }
+
+    fn enqueue(
+        &self,
+        session_state: &SessionState,
+        ctx: &CallContext,
+        session_tx: mpsc::Sender<ApiMessage>,
+        message: ApiMessage,
+    ) {
+        let outbox = self.get_or_start_outbox(&ctx.session_id, session_tx);
+        let mut pending = outbox.pending.lock().unwrap();
+        if pending.len() >= self.config.max_pending {
+            let dropped = match self.config.slow_consumer_policy {
+                SlowConsumerPolicy::Backpressure | SlowConsumerPolicy::DropOldest => {
+                    pending.pop_front()
+                }
+                SlowConsumerPolicy::Coalesce => {
+                    match pending.iter().position(|p| p.ctx.method == ctx.method) {
+                        Some(index) => pending.remove(index),
+                        None => pending.pop_front(),
+                    }
+                }
+                SlowConsumerPolicy::Disconnect => {
+                    warn!(
+                        "Disconnecting {} which has {} events pending",
+                        ctx.app_id,
+                        pending.len()
+                    );
+                    for dropped in pending.drain(..) {
+                        self.count_dropped(&dropped.ctx);
+                    }
+                    self.count_dropped(ctx);
+                    if let Some(session) = session_state.get_session(&ctx.session_id) {
+                        session.disconnect();
+                    }
+                    return;
+                }
+            };
+            if let Some(dropped) = dropped {
+                self.count_dropped(&dropped.ctx);
+            }
+        }
+        pending.push_back(PendingEvent {
+            ctx: ctx.clone(),
+            message,
+            queued_at: Instant::now(),
+        });
+        outbox.notify.notify_one();
+    }
+
+    fn get_or_start_outbox(
+        &self,
+        session_id: &str,
+        session_tx: mpsc::Sender<ApiMessage>,
+    ) -> Arc<Outbox> {
+        let mut outboxes = self.outboxes.write().unwrap();
+        if let Some(outbox) = outboxes.get(session_id) {
+            return outbox.clone();
+        }
+        let outbox = Arc::new(Outbox::default());
+        outboxes.insert(session_id.to_owned(), outbox.clone());
+        let delivery = self.clone();
+        let pump_outbox = outbox.clone();
+        let session_id = session_id.to_owned();
+        tokio::spawn(async move {
+            delivery.pump(pump_outbox.clone(), session_tx).await;
+            // the session went away without being removed, drop its outbox as well
+            let mut outboxes = delivery.outboxes.write().unwrap();
+            if let Some(outbox) = outboxes.get(&session_id) {
+                if Arc::ptr_eq(outbox, &pump_outbox) {
+                    outboxes.remove(&session_id);
+                }
+            }
+        });
+        outbox
+    }
+
+    /// Writes the pending events to the session until it's removed or its channel closes.
+    async fn pump(&self, outbox: Arc<Outbox>, session_tx: mpsc::Sender<ApiMessage>) {
+        loop {
+            let next = outbox.pending.lock().unwrap().pop_front();
+            match next {
+                Some(event) => {
+                    tokio::select! {
+                        result = session_tx.send(event.message) => match result {
+                            Ok(_) => self.count_delivered(&event.ctx, event.queued_at.elapsed()),
+                            Err(_) => self.count_dropped(&event.ctx),
+                        },
+                        _ = outbox.closing.notified() => break,
+                    }
+                }
+                None => {
+                    tokio::select! {
+                        _ = outbox.notify.notified() => {}
+                        _ = outbox.closing.notified() => break,
+                        _ = session_tx.closed() => break,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Stops delivering to the session, its pending events are discarded.
+    pub fn remove_session(&self, session_id: &str) {
+        if let Some(outbox) = self.outboxes.write().unwrap().remove(session_id) {
+            outbox.closing.notify_one();
+        }
+        self.metrics.write().unwrap().sessions.remove(session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ripple_sdk::{
+        api::{apps::EffectiveTransport, gateway::rpc_gateway_api::ApiProtocol},
+        tokio::{self, time::timeout},
+    };
+
+    use super::*;
+    use crate::state::session_state::Session;
+
+    fn ctx(session_id: &str, event: &str) -> CallContext {
+        CallContext::new(
+            session_id.into(),
+            "request".into(),
+            "app".into(),
+            1,
+            ApiProtocol::JsonRpc,
+            event.into(),
+        )
+    }
+
+    fn delivery(policy: SlowConsumerPolicy) -> EventDelivery {
+        EventDelivery::new(EventDeliveryConfig {
+            max_pending: 2,
+            slow_consumer_policy: policy,
+        })
+    }
+
+    /// Sends events named by `events` without giving the session a chance to read them.
+    async fn send_all(
+        delivery: &EventDelivery,
+        session_state: &SessionState,
+        session_tx: &mpsc::Sender<ApiMessage>,
+        events: &[&str],
+    ) {
+        for (i, event) in events.iter().enumerate() {
+            let message = ApiMessage::new(ApiProtocol::JsonRpc, i.to_string(), "request".into());
+            delivery
+                .send(
+                    session_state,
+                    &ctx("session", event),
+                    session_tx.clone(),
+                    message,
+                )
+                .await;
+        }
+    }
+
+    async fn received(rx: &mut mpsc::Receiver<ApiMessage>) -> Vec<String> {
+        let mut messages = Vec::new();
+        while let Ok(Some(message)) = timeout(Duration::from_millis(50), rx.recv()).await {
+            messages.push(message.jsonrpc_msg);
+        }
+        messages
+    }
+
+    fn totals(delivery: &EventDelivery) -> (u64, u64, u64) {
+        let metrics = delivery.get_metrics();
+        let totals = &metrics.sessions.get("app#1").unwrap().totals;
+        (totals.emitted, totals.delivered, totals.dropped)
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn backpressure_delivers_everything() {
+        let delivery = delivery(SlowConsumerPolicy::Backpressure);
+        let (tx, mut rx) = mpsc::channel(8);
+        send_all(&delivery, &SessionState::default(), &tx, &["a", "b", "c"]).await;
+        assert_eq!(received(&mut rx).await, vec!["0", "1", "2"]);
+        assert_eq!(totals(&delivery), (3, 3, 0));
+        assert!(delivery.outboxes.read().unwrap().is_empty());
+
+        drop(rx);
+        send_all(&delivery, &SessionState::default(), &tx, &["d"]).await;
+        assert_eq!(totals(&delivery), (4, 3, 1));
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn drop_oldest_drops_the_oldest_pending_event() {
+        let delivery = delivery(SlowConsumerPolicy::DropOldest);
+        let (tx, mut rx) = mpsc::channel(8);
+        send_all(&delivery, &SessionState::default(), &tx, &["a", "b", "c"]).await;
+        assert_eq!(received(&mut rx).await, vec!["1", "2"]);
+        assert_eq!(totals(&delivery), (3, 2, 1));
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn coalesce_replaces_the_pending_event_of_the_same_name() {
+        let delivery = delivery(SlowConsumerPolicy::Coalesce);
+        let (tx, mut rx) = mpsc::channel(8);
+        send_all(
+            &delivery,
+            &SessionState::default(),
+            &tx,
+            &["a", "b", "a", "c"],
+        )
+        .await;
+        // the second "a" replaces the first one, "c" then finds no "c" and drops "b"
+        assert_eq!(received(&mut rx).await, vec!["2", "3"]);
+        assert_eq!(totals(&delivery), (4, 2, 2));
+        let events = delivery.get_metrics().events;
+        assert_eq!(events.get("a").unwrap().dropped, 1);
+        assert_eq!(events.get("b").unwrap().dropped, 1);
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn disconnect_closes_the_session() {
+        let delivery = delivery(SlowConsumerPolicy::Disconnect);
+        let session_state = SessionState::default();
+        let (tx, mut rx) = mpsc::channel(8);
+        let session = Session::new(
+            "app".into(),
+            Some(tx.clone()),
+            EffectiveTransport::Websocket,
+        );
+        session_state.add_session("session".into(), session.clone());
+        send_all(&delivery, &session_state, &tx, &["a", "b", "c"]).await;
+        assert!(timeout(Duration::from_millis(50), session.disconnected())
+            .await
+            .is_ok());
+        assert!(received(&mut rx).await.is_empty());
+        assert_eq!(totals(&delivery), (3, 0, 3));
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn closed_session_stops_its_outbox() {
+        let delivery = delivery(SlowConsumerPolicy::DropOldest);
+        let (tx, rx) = mpsc::channel(8);
+        drop(rx);
+        send_all(&delivery, &SessionState::default(), &tx, &["a"]).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(delivery.outboxes.read().unwrap().is_empty());
+        assert_eq!(totals(&delivery), (1, 0, 1));
+    }
+
+    #[test]
+    fn counters_are_reported_by_app_and_session_index() {
+        let delivery = delivery(SlowConsumerPolicy::DropOldest);
+        delivery.count_emitted(&ctx("first", "a"));
+        delivery.count_delivered(&ctx("first", "a"), Duration::from_millis(10));
+        delivery.count_emitted(&ctx("second", "a"));
+        delivery.count_delivered(&ctx("second", "a"), Duration::from_millis(30));
+
+        let metrics = delivery.get_metrics();
+        assert!(metrics.sessions.contains_key("app#1"));
+        assert!(metrics.sessions.contains_key("app#2"));
+        let a = metrics.events.get("a").unwrap();
+        assert_eq!((a.emitted, a.delivered), (2, 2));
+        assert_eq!((a.avg_latency_ms, a.max_latency_ms), (20, 30));
+
+        delivery.remove_session("first");
+        let metrics = delivery.get_metrics();
+        assert_eq!(metrics.sessions.len(), 1);
+        assert_eq!(metrics.events.get("a").unwrap().emitted, 2);
+    }
+}
@@ -17,4 +17,5 @@
 
 pub mod app_events;
 pub mod delegated_launcher_handler;
+pub mod event_delivery;
 pub mod provider_broker;
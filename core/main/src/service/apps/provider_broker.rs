@@ -15,31 +15,38 @@
 // SPDX-License-Identifier: Apache-2.0
 //
 
-use arrayvec::ArrayVec;
+use jsonrpsee::{core::Error, types::error::CallError};
 use ripple_sdk::{
     api::{
         firebolt::{
-            fb_capabilities::{CapEvent, FireboltCap},
+            fb_capabilities::{CapEvent, FireboltCap, CAPABILITY_NOT_AVAILABLE},
             fb_general::ListenRequest,
+            fb_keyboard::KEYBOARD_PROVIDER_CAPABILITY,
             fb_lifecycle_management::{
                 LifecycleManagementEventRequest, LifecycleManagementProviderEvent,
             },
+            fb_pin::PIN_CHALLENGE_CAPABILITY,
             provider::{
-                FocusRequest, ProviderRequest, ProviderRequestPayload, ProviderResponse,
-                ProviderResponsePayload,
+                ChallengeResponse, FallbackProviderRequest, FocusRequest, ProviderRequest,
+                ProviderRequestPayload, ProviderResponse, ProviderResponsePayload,
+                ACK_CHALLENGE_CAPABILITY,
             },
         },
         gateway::rpc_gateway_api::CallContext,
+        manifest::device_manifest::{ProviderBrokerConfig, ProviderSelection},
     },
+    extn::extn_client_message::ExtnResponse,
+    framework::ripple_contract::RippleContract,
     log::{debug, error, info, warn},
     serde_json,
-    tokio::sync::oneshot,
+    tokio::{self, sync::oneshot, time::Duration},
     utils::channel_utils::oneshot_send_and_log,
     uuid::Uuid,
 };
 use serde::{Deserialize, Serialize};
 
 use std::{
+    cmp::Ordering,
     collections::HashMap,
     sync::{Arc, RwLock},
 };
@@ -49,21 +56,41 @@ use crate::{
     state::{cap::cap_state::CapState, platform_state::PlatformState},
 };
 
-const REQUEST_QUEUE_CAPACITY: usize = 3;
-
 #[derive(Serialize, Deserialize, Debug)]
 pub enum ProviderError {
     General,
     NotFound,
     NotSupported,
     IoError,
+    /// The provider didn't respond within the timeout configured for the capability.
+    Timeout,
+    /// Too many requests are already waiting for a provider to register.
+    QueueFull,
+}
+
+impl From<ProviderError> for Error {
+    fn from(error: ProviderError) -> Self {
+        let message = match error {
+            ProviderError::Timeout => "Provider did not respond in time".to_owned(),
+            ProviderError::QueueFull => "Too many requests waiting for a provider".to_owned(),
+            e => format!("Provider error {:?}", e),
+        };
+        Error::Call(CallError::Custom {
+            code: CAPABILITY_NOT_AVAILABLE,
+            message,
+            data: None,
+        })
+    }
 }
 
+pub type ProviderBrokerResponse = Result<ProviderResponsePayload, ProviderError>;
+
 #[derive(Clone, Default)]
 pub struct ProviderBrokerState {
-    provider_methods: Arc<RwLock<HashMap<String, ProviderMethod>>>,
+    /// Providers of each capability method in the order they registered.
+    provider_methods: Arc<RwLock<HashMap<String, Vec<ProviderMethod>>>>,
     active_sessions: Arc<RwLock<HashMap<String, ProviderSession>>>,
-    request_queue: Arc<RwLock<ArrayVec<ProviderBrokerRequest, REQUEST_QUEUE_CAPACITY>>>,
+    request_queue: Arc<RwLock<Vec<ProviderBrokerRequest>>>,
 }
 
 impl std::fmt::Debug for ProviderBrokerState {
@@ -83,7 +110,10 @@ struct ProviderMethod {
 struct ProviderSession {
     caller: ProviderCaller,
     provider: ProviderMethod,
-    _capability: String,
+    capability: String,
+    method: String,
+    request: ProviderRequestPayload,
+    app_id: Option<String>,
     focused: bool,
 }
 
@@ -92,13 +122,13 @@ pub struct ProviderBrokerRequest {
     pub method: String,
     pub caller: CallContext,
     pub request: ProviderRequestPayload,
-    pub tx: oneshot::Sender<ProviderResponsePayload>,
+    pub tx: oneshot::Sender<ProviderBrokerResponse>,
     pub app_id: Option<String>,
 }
 
 struct ProviderCaller {
     identity: CallContext,
-    tx: oneshot::Sender<ProviderResponsePayload>,
+    tx: oneshot::Sender<ProviderBrokerResponse>,
 }
 
 #[derive(Debug, Serialize, Default, Clone)]
@@ -143,16 +173,35 @@ impl ProviderBroker {
         method: String,
         provider: CallContext,
     ) {
-        let mut provider_methods = pst.provider_broker_state.provider_methods.write().unwrap();
         let cap_method = format!("{}:{}", capability, method);
-        if let Some(method) = provider_methods.get(&cap_method) {
-            // unregister the capability if it is provided by the session
-            // that is making the unregister call
-            if method.provider.session_id == provider.session_id {
-                provider_methods.remove(&cap_method);
+        {
+            let mut provider_methods = pst.provider_broker_state.provider_methods.write().unwrap();
+            if let Some(providers) = provider_methods.get_mut(&cap_method) {
+                // unregister the capability only for the session
+                // that is making the unregister call
+                providers.retain(|p| p.provider.session_id != provider.session_id);
+                if providers.is_empty() {
+                    provider_methods.remove(&cap_method);
+                }
+                ProviderBroker::remove_request(&pst, &provider.app_id.clone(), &capability);
             }
-            ProviderBroker::remove_request(&pst, &provider.app_id.clone(), &capability);
         }
+        let orphaned = {
+            let mut active_sessions = pst.provider_broker_state.active_sessions.write().unwrap();
+            let c_ids: Vec<String> = active_sessions
+                .iter()
+                .filter(|(_, session)| {
+                    session.provider.provider.session_id == provider.session_id
+                        && format!("{}:{}", session.capability, session.method) == cap_method
+                })
+                .map(|(c_id, _)| c_id.clone())
+                .collect();
+            c_ids
+                .iter()
+                .filter_map(|c_id| active_sessions.remove(c_id))
+                .collect()
+        };
+        ProviderBroker::failover(pst, orphaned).await;
 
         // TODO Add permissions
     }
@@ -179,18 +228,18 @@ impl ProviderBroker {
         );
         {
             let mut provider_methods = pst.provider_broker_state.provider_methods.write().unwrap();
-            provider_methods.insert(
-                cap_method,
-                ProviderMethod {
-                    event_name,
-                    provider,
-                },
-            );
+            let providers = provider_methods.entry(cap_method).or_default();
+            // registering again makes the session the most recent provider
+            providers.retain(|p| p.provider.session_id != provider.session_id);
+            providers.push(ProviderMethod {
+                event_name,
+                provider,
+            });
         }
         let existing = ProviderBroker::remove_request(&pst, &provider_app_id, &capability);
         if let Some(request) = existing {
             info!("register_provider: Found pending provider request, invoking");
-            ProviderBroker::invoke_method(&pst, request).await;
+            ProviderBroker::dispatch(&pst, request).await;
         }
 
         CapState::emit(
@@ -205,67 +254,226 @@ impl ProviderBroker {
     pub fn get_provider_methods(pst: &PlatformState) -> ProviderResult {
         let provider_methods = pst.provider_broker_state.provider_methods.read().unwrap();
         let mut result: HashMap<String, Vec<String>> = HashMap::new();
-        let caps_keys = provider_methods.keys();
-        let all_caps = caps_keys.cloned().collect::<Vec<String>>();
-        for cap in all_caps {
-            if let Some(provider) = provider_methods.get(&cap) {
-                if let Some(list) = result.get_mut(&provider.provider.app_id) {
-                    list.push(String::from(provider.event_name));
-                } else {
-                    result.insert(
-                        provider.provider.app_id.clone(),
-                        vec![String::from(provider.event_name)],
-                    );
-                }
+        for provider in provider_methods.values().flatten() {
+            if let Some(list) = result.get_mut(&provider.provider.app_id) {
+                list.push(String::from(provider.event_name));
+            } else {
+                result.insert(
+                    provider.provider.app_id.clone(),
+                    vec![String::from(provider.event_name)],
+                );
             }
         }
         ProviderResult::new(result)
     }
 
-    pub async fn invoke_method(pst: &PlatformState, request: ProviderBrokerRequest) {
+    pub async fn invoke_method(pst: &PlatformState, mut request: ProviderBrokerRequest) {
+        let timeout_ms = pst
+            .get_device_manifest()
+            .capabilities
+            .provider_broker
+            .response_timeouts_ms
+            .get(&request.capability)
+            .cloned();
+        if let Some(timeout_ms) = timeout_ms {
+            // The caller is answered from here, the session or queued request is left with a
+            // closed channel on timeout which is how it gets cleaned up.
+            let (tx, rx) = oneshot::channel::<ProviderBrokerResponse>();
+            let caller_tx = std::mem::replace(&mut request.tx, tx);
+            let capability = request.capability.clone();
+            let state = pst.clone();
+            tokio::spawn(async move {
+                let response =
+                    match tokio::time::timeout(Duration::from_millis(timeout_ms), rx).await {
+                        Ok(Ok(response)) => response,
+                        Ok(Err(_)) => Err(ProviderError::General),
+                        Err(_) => {
+                            warn!("Provider for {} did not respond in time", capability);
+                            Err(ProviderError::Timeout)
+                        }
+                    };
+                if matches!(response, Err(ProviderError::Timeout)) {
+                    ProviderBroker::remove_abandoned(&state);
+                }
+                oneshot_send_and_log(caller_tx, response, "ProviderTimeout");
+            });
+        }
+        ProviderBroker::dispatch(pst, request).await
+    }
+
+    async fn dispatch(pst: &PlatformState, request: ProviderBrokerRequest) {
         let cap_method = format!("{}:{}", request.capability, request.method);
         debug!("invoking provider for {}", cap_method);
 
-        let provider_opt = {
-            let provider_methods = pst.provider_broker_state.provider_methods.read().unwrap();
-            provider_methods.get(&cap_method).cloned()
-        };
+        let provider_opt = ProviderBroker::select_provider(
+            pst,
+            &cap_method,
+            &request.capability,
+            request.app_id.as_ref(),
+        );
         if let Some(provider) = provider_opt {
-            let event_name = provider.event_name.clone();
+            let event_name = provider.event_name;
+            let app_id = provider.provider.app_id.clone();
             let req_params = request.request.clone();
-            let app_id_opt = request.app_id.clone();
             let c_id = ProviderBroker::start_provider_session(&pst, request, provider);
-            if let Some(app_id) = app_id_opt {
-                debug!("Sending request to specific app {}", app_id);
-                AppEvents::emit_to_app(
-                    &pst,
-                    app_id,
-                    event_name,
-                    &serde_json::to_value(ProviderRequest {
-                        correlation_id: c_id,
-                        parameters: req_params,
-                    })
-                    .unwrap(),
-                )
-                .await;
-            } else {
-                debug!("Broadcasting request to all the apps!!");
-                AppEvents::emit(
-                    pst,
-                    event_name,
-                    &serde_json::to_value(ProviderRequest {
-                        correlation_id: c_id,
-                        parameters: req_params,
-                    })
-                    .unwrap(),
-                )
-                .await;
-            }
+            debug!("Sending request to provider app {}", app_id);
+            AppEvents::emit_to_app(
+                &pst,
+                app_id,
+                event_name,
+                &serde_json::to_value(ProviderRequest {
+                    correlation_id: c_id,
+                    parameters: req_params,
+                })
+                .unwrap(),
+            )
+            .await;
+        } else if request.app_id.is_none() && ProviderBroker::has_fallback(pst, &request.capability)
+        {
+            ProviderBroker::invoke_fallback(pst, request);
         } else {
             ProviderBroker::queue_provider_request(pst, request);
         }
     }
 
+    /// Whether the fallback provider extension serves the capability when no app provides it,
+    /// it only answers challenges and keyboard sessions.
+    fn has_fallback(pst: &PlatformState, capability: &str) -> bool {
+        [
+            PIN_CHALLENGE_CAPABILITY,
+            ACK_CHALLENGE_CAPABILITY,
+            KEYBOARD_PROVIDER_CAPABILITY,
+        ]
+        .contains(&capability)
+            && pst
+                .get_client()
+                .get_extn_client()
+                .has_contract(RippleContract::FallbackProvider)
+    }
+
+    /// Hands a request nobody provides to the fallback provider extension.
+    fn invoke_fallback(pst: &PlatformState, request: ProviderBrokerRequest) {
+        debug!("No provider for {}, using fallback", request.capability);
+        let client = pst.get_client();
+        tokio::spawn(async move {
+            let fallback_request = FallbackProviderRequest {
+                capability: request.capability,
+                method: request.method,
+                parameters: request.request,
+            };
+            let response = match client.send_extn_request(fallback_request).await {
+                Ok(msg) => match msg.payload.extract() {
+                    Some(ExtnResponse::PinChallenge(r)) => {
+                        Ok(ProviderResponsePayload::PinChallengeResponse(r))
+                    }
+                    Some(ExtnResponse::Keyboard(r)) => {
+                        Ok(ProviderResponsePayload::KeyboardResult(r))
+                    }
+                    Some(ExtnResponse::Boolean(granted)) => Ok(
+                        ProviderResponsePayload::ChallengeResponse(ChallengeResponse { granted }),
+                    ),
+                    _ => Err(ProviderError::NotSupported),
+                },
+                Err(e) => {
+                    error!("Fallback provider failed {:?}", e);
+                    Err(ProviderError::General)
+                }
+            };
+            oneshot_send_and_log(request.tx, response, "FallbackProvider");
+        });
+    }
+
+    /// Picks the provider for a request from the ones registered for the capability method
+    /// using the selection criteria of the device manifest.
+    fn select_provider(
+        pst: &PlatformState,
+        cap_method: &str,
+        capability: &str,
+        app_id: Option<&String>,
+    ) -> Option<ProviderMethod> {
+        let providers = {
+            let provider_methods = pst.provider_broker_state.provider_methods.read().unwrap();
+            provider_methods
+                .get(cap_method)
+                .cloned()
+                .unwrap_or_default()
+        };
+        let config = pst.get_device_manifest().capabilities.provider_broker;
+        ProviderBroker::pick_provider(providers, &config, capability, app_id, |app_id| {
+            pst.app_manager_state.is_foreground(app_id)
+        })
+    }
+
+    /// Picks from the providers, given in registration order, by the selection criteria of
+    /// the config. Requests addressed to an app are only given to that app.
+    fn pick_provider<F>(
+        providers: Vec<ProviderMethod>,
+        config: &ProviderBrokerConfig,
+        capability: &str,
+        app_id: Option<&String>,
+        is_foreground: F,
+    ) -> Option<ProviderMethod>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let priorities = config
+            .priorities
+            .get(capability)
+            .cloned()
+            .unwrap_or_default();
+        let rank = |p: &ProviderMethod| {
+            priorities
+                .iter()
+                .position(|app_id| app_id.eq(&p.provider.app_id))
+                .unwrap_or(usize::MAX)
+        };
+        let is_focused = |p: &ProviderMethod| is_foreground(&p.provider.app_id);
+        providers
+            .into_iter()
+            .enumerate()
+            .filter(|(_, p)| match app_id {
+                Some(app_id) => app_id.eq(&p.provider.app_id),
+                None => true,
+            })
+            .min_by(|(a_order, a), (b_order, b)| {
+                config
+                    .selection
+                    .iter()
+                    .fold(Ordering::Equal, |ordering, selection| {
+                        ordering.then_with(|| match selection {
+                            ProviderSelection::Priority => rank(a).cmp(&rank(b)),
+                            ProviderSelection::Focused => is_focused(b).cmp(&is_focused(a)),
+                            ProviderSelection::MostRecent => b_order.cmp(a_order),
+                        })
+                    })
+            })
+            .map(|(_, p)| p)
+    }
+
+    /// Hands the requests of sessions whose provider went away to the next provider, they
+    /// are queued when no other provider is registered.
+    async fn failover(pst: &PlatformState, sessions: Vec<ProviderSession>) {
+        for session in sessions {
+            ProviderBroker::end_focus(pst, &session);
+            if session.caller.tx.is_closed() {
+                continue;
+            }
+            info!(
+                "Provider {} went away, failing over request for {}",
+                session.provider.provider.app_id, session.capability
+            );
+            let request = ProviderBrokerRequest {
+                capability: session.capability,
+                method: session.method,
+                caller: session.caller.identity,
+                request: session.request,
+                tx: session.caller.tx,
+                app_id: session.app_id,
+            };
+            ProviderBroker::dispatch(pst, request).await;
+        }
+    }
+
     fn start_provider_session(
         pst: &PlatformState,
         request: ProviderBrokerRequest,
@@ -281,7 +489,10 @@ impl ProviderBroker {
                     tx: request.tx,
                 },
                 provider: provider.clone(),
-                _capability: request.capability,
+                capability: request.capability,
+                method: request.method,
+                request: request.request,
+                app_id: request.app_id,
                 focused: false,
             },
         );
@@ -292,29 +503,67 @@ impl ProviderBroker {
         // Remove any duplicate requests.
         ProviderBroker::remove_request(pst, &request.caller.app_id, &request.capability);
 
+        let queue_size = pst
+            .get_device_manifest()
+            .capabilities
+            .provider_broker
+            .queue_size;
         let mut request_queue = pst.provider_broker_state.request_queue.write().unwrap();
-        if request_queue.is_full() {
-            warn!("invoke_method: Request queue full, removing oldest request");
-            request_queue.remove(0);
+        request_queue.retain(|r| !r.tx.is_closed());
+        if request_queue.len() >= queue_size {
+            warn!(
+                "invoke_method: Request queue full, rejecting request for {}",
+                request.capability
+            );
+            oneshot_send_and_log(request.tx, Err(ProviderError::QueueFull), "QueueFull");
+            return;
         }
         request_queue.push(request);
     }
 
+    /// Drops the sessions and queued requests whose caller stopped waiting.
+    fn remove_abandoned(pst: &PlatformState) {
+        pst.provider_broker_state
+            .request_queue
+            .write()
+            .unwrap()
+            .retain(|r| !r.tx.is_closed());
+        let abandoned: Vec<ProviderSession> = {
+            let mut active_sessions = pst.provider_broker_state.active_sessions.write().unwrap();
+            let c_ids: Vec<String> = active_sessions
+                .iter()
+                .filter(|(_, session)| session.caller.tx.is_closed())
+                .map(|(c_id, _)| c_id.clone())
+                .collect();
+            c_ids
+                .iter()
+                .filter_map(|c_id| active_sessions.remove(c_id))
+                .collect()
+        };
+        for session in abandoned {
+            ProviderBroker::end_focus(pst, &session);
+        }
+    }
+
+    fn end_focus(pst: &PlatformState, session: &ProviderSession) {
+        if session.focused {
+            let app_id = session.provider.provider.app_id.clone();
+            let event = LifecycleManagementEventRequest::Provide(
+                LifecycleManagementProviderEvent::Remove(app_id),
+            );
+            let client = pst.clone().get_client();
+            if let Err(e) = client.send_event(event) {
+                error!("send event error {:?}", e);
+            }
+        }
+    }
+
     pub async fn provider_response(pst: &PlatformState, resp: ProviderResponse) {
         let mut active_sessions = pst.provider_broker_state.active_sessions.write().unwrap();
         match active_sessions.remove(&resp.correlation_id) {
             Some(session) => {
-                oneshot_send_and_log(session.caller.tx, resp.result, "ProviderResponse");
-                if session.focused {
-                    let app_id = session.provider.provider.app_id.clone();
-                    let event = LifecycleManagementEventRequest::Provide(
-                        LifecycleManagementProviderEvent::Remove(app_id),
-                    );
-                    let client = pst.clone().get_client();
-                    if let Err(e) = client.send_event(event) {
-                        error!("send event error {:?}", e);
-                    }
-                }
+                ProviderBroker::end_focus(pst, &session);
+                oneshot_send_and_log(session.caller.tx, Ok(resp.result), "ProviderResponse");
             }
             None => {
                 error!("Ignored provider response because there was no active session waiting")
@@ -322,49 +571,61 @@ impl ProviderBroker {
         }
     }
 
-    fn cleanup_caps_for_unregister(pst: &PlatformState, session_id: String) -> Vec<String> {
-        let mut active_sessions = pst.provider_broker_state.active_sessions.write().unwrap();
-        let cid_keys = active_sessions.keys();
-        let all_cids = cid_keys.cloned().collect::<Vec<String>>();
-        let mut clear_cids = Vec::<String>::new();
-        // find all the sessions where either the caller or the provider are being unregistered and clear that session
-        // the oneshot for the caller should then get descoped and called with an error
-        for cid in all_cids {
-            if let Some(session) = active_sessions.get(&cid) {
-                if session.caller.identity.session_id == session_id
-                    || session.provider.provider.session_id == session_id
-                {
-                    clear_cids.push(cid);
+    fn cleanup_caps_for_unregister(
+        pst: &PlatformState,
+        session_id: String,
+    ) -> (Vec<String>, Vec<ProviderSession>) {
+        let mut orphaned = Vec::new();
+        {
+            let mut active_sessions = pst.provider_broker_state.active_sessions.write().unwrap();
+            // find all the sessions where either the caller or the provider are being unregistered and clear that session
+            // the oneshot for the caller should then get descoped and called with an error,
+            // sessions which only lost their provider are returned to be failed over
+            let clear_cids: Vec<String> = active_sessions
+                .iter()
+                .filter(|(_, session)| {
+                    session.caller.identity.session_id == session_id
+                        || session.provider.provider.session_id == session_id
+                })
+                .map(|(cid, _)| cid.clone())
+                .collect();
+            for cid in clear_cids {
+                if let Some(session) = active_sessions.remove(&cid) {
+                    if session.caller.identity.session_id != session_id {
+                        orphaned.push(session);
+                    }
                 }
             }
         }
-
-        for cid in clear_cids {
-            active_sessions.remove(&cid);
-        }
         let mut provider_methods = pst.provider_broker_state.provider_methods.write().unwrap();
-        // find all providers for the session being unregistered
-        // remove the provided capability
+        // remove the providers of the session being unregistered, capabilities without any
+        // provider left for any of their methods are returned
         let mut clear_caps = Vec::new();
-        let caps_keys = provider_methods.keys();
-        let all_caps = caps_keys.cloned().collect::<Vec<String>>();
-        for cap in all_caps {
-            if let Some(provider) = provider_methods.get(&cap) {
-                if provider.provider.session_id == session_id {
-                    clear_caps.push(cap);
+        provider_methods.retain(|cap_method, providers| {
+            providers.retain(|p| p.provider.session_id != session_id);
+            if providers.is_empty() {
+                if let Some((cap, _)) = cap_method.rsplit_once(':') {
+                    clear_caps.push(cap.to_owned());
                 }
             }
-        }
-        for cap in clear_caps.clone() {
-            provider_methods.remove(&cap);
-        }
-        clear_caps
+            !providers.is_empty()
+        });
+        clear_caps.sort();
+        clear_caps.dedup();
+        clear_caps.retain(|cap| {
+            let prefix = format!("{}:", cap);
+            !provider_methods.keys().any(|k| k.starts_with(&prefix))
+        });
+        (clear_caps, orphaned)
     }
 
     pub async fn unregister_session(pst: &PlatformState, session_id: String) {
-        let cleaned_caps = Self::cleanup_caps_for_unregister(&pst.clone(), session_id);
+        let (cleaned_caps, orphaned) = Self::cleanup_caps_for_unregister(&pst.clone(), session_id);
+        Self::failover(pst, orphaned).await;
+        // the fallback provider keeps serving its capabilities without an app provider
         let caps: Vec<FireboltCap> = cleaned_caps
             .iter()
+            .filter(|x| !Self::has_fallback(pst, x))
             .map(|x| FireboltCap::Full(x.clone()))
             .collect();
         for cap in caps {
@@ -412,3 +673,341 @@ impl ProviderBroker {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ripple_sdk::{
+        api::{
+            device::device_user_grants_data::GrantStep,
+            firebolt::fb_capabilities::{CapabilityRole, FireboltPermission},
+            gateway::rpc_gateway_api::ApiProtocol,
+            manifest::{
+                device_manifest::DeviceManifest,
+                extn_manifest::{ExtnManifest, ExtnSymbol},
+            },
+        },
+        crossbeam::channel::unbounded,
+        extn::{
+            extn_client_message::ExtnMessage,
+            extn_id::{ExtnClassId, ExtnId},
+            ffi::ffi_message::CExtnMessage,
+        },
+    };
+
+    use super::*;
+    use crate::{
+        service::{extn::ripple_client::RippleClient, user_grants::GrantStepExecutor},
+        state::bootstrap_state::ChannelsState,
+    };
+
+    const ACK_CHALLENGE: &str = "xrn:firebolt:capability:usergrant:acknowledgechallenge";
+    const ENTITY_INFO: &str = "xrn:firebolt:capability:discovery:entity-info";
+
+    fn manifests() -> (ExtnManifest, DeviceManifest) {
+        let (_, extn_manifest) = ExtnManifest::load_from_content(
+            include_str!("../../../../../examples/manifest/extn-manifest-example.json").to_owned(),
+        )
+        .unwrap();
+        let (_, manifest) = DeviceManifest::load_from_content(
+            include_str!("../../../../../examples/manifest/device-manifest-example.json")
+                .to_owned(),
+        )
+        .unwrap();
+        (extn_manifest, manifest)
+    }
+
+    /// Platform state without a fallback provider extension.
+    async fn platform_state() -> PlatformState {
+        let (extn_manifest, manifest) = manifests();
+        let channels = ChannelsState::new();
+        let _ = channels.get_app_mgr_receiver();
+        let client = RippleClient::new(channels);
+        client.init().await;
+        PlatformState::new(extn_manifest, manifest, client, Vec::new())
+    }
+
+    /// Platform state whose fallback provider extension answers every request with `answer`.
+    async fn platform_state_with_fallback(answer: ExtnResponse) -> PlatformState {
+        let (extn_manifest, manifest) = manifests();
+        let channels = ChannelsState::new();
+        // without an app manager the app name lookups of the grant flow fail right away
+        let _ = channels.get_app_mgr_receiver();
+        let client = RippleClient::new(channels.clone());
+
+        let fallback_id = ExtnId::new_extn(ExtnClassId::Distributor, "general".into());
+        let (fallback_tx, fallback_rx) = unbounded::<CExtnMessage>();
+        client.get_extn_client().add_sender(
+            fallback_id.clone(),
+            ExtnSymbol {
+                id: fallback_id.to_string(),
+                uses: Vec::new(),
+                fulfills: vec![RippleContract::FallbackProvider.as_clear_string()],
+            },
+            fallback_tx,
+        );
+        let main_tx = channels.get_extn_sender();
+        std::thread::spawn(move || {
+            while let Ok(c_message) = fallback_rx.recv() {
+                let message: ExtnMessage = c_message.try_into().unwrap();
+                let response = message.get_response(answer.clone()).unwrap();
+                let _ = main_tx.send(response.into());
+            }
+        });
+        client.init().await;
+        PlatformState::new(extn_manifest, manifest, client, Vec::new())
+    }
+
+    fn call_context(session_id: &str, app_id: &str) -> CallContext {
+        CallContext::new(
+            session_id.into(),
+            "request".into(),
+            app_id.into(),
+            1,
+            ApiProtocol::JsonRpc,
+            "method".into(),
+        )
+    }
+
+    async fn execute_ack_challenge(pst: &PlatformState) -> bool {
+        let step = GrantStep {
+            capability: ACK_CHALLENGE.into(),
+            configuration: None,
+        };
+        let permission = FireboltPermission {
+            cap: FireboltCap::Full("xrn:firebolt:capability:localization:postal-code".into()),
+            role: CapabilityRole::Use,
+        };
+        GrantStepExecutor::execute(&step, pst, &call_context("caller", "app"), &permission)
+            .await
+            .is_ok()
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn fallback_serves_grant_after_last_provider_leaves() {
+        let pst = platform_state_with_fallback(ExtnResponse::Boolean(true)).await;
+        ProviderBroker::register_provider(
+            &pst,
+            ACK_CHALLENGE.into(),
+            "challenge".into(),
+            "acknowledgechallenge.onRequestChallenge",
+            call_context("provider", "refui"),
+            ListenRequest { listen: true },
+        )
+        .await;
+        ProviderBroker::unregister_session(&pst, "provider".into()).await;
+
+        assert!(pst
+            .cap_state
+            .generic
+            .check_available(&vec![FireboltCap::Full(ACK_CHALLENGE.into())])
+            .is_ok());
+        assert!(execute_ack_challenge(&pst).await);
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn fallback_leaves_other_capabilities_unavailable() {
+        let pst = platform_state_with_fallback(ExtnResponse::Boolean(true)).await;
+        register(&pst, ENTITY_INFO, "provider").await;
+        ProviderBroker::unregister_session(&pst, "provider".into()).await;
+
+        assert!(pst
+            .cap_state
+            .generic
+            .check_available(&vec![FireboltCap::Full(ENTITY_INFO.into())])
+            .is_err());
+        let _rx = invoke(&pst, ENTITY_INFO).await;
+        assert_eq!(
+            pst.provider_broker_state
+                .request_queue
+                .read()
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn fallback_denial_denies_grant() {
+        let pst = platform_state_with_fallback(ExtnResponse::Boolean(false)).await;
+        assert!(!execute_ack_challenge(&pst).await);
+    }
+
+    fn provider(app_id: &str) -> ProviderMethod {
+        ProviderMethod {
+            event_name: "acknowledgechallenge.onRequestChallenge",
+            provider: call_context(app_id, app_id),
+        }
+    }
+
+    /// App id of the provider picked among `app_ids`, registered in that order, while
+    /// `focused` is in the foreground.
+    fn pick(
+        app_ids: &[&str],
+        config: &ProviderBrokerConfig,
+        app_id: Option<&str>,
+        focused: &str,
+    ) -> Option<String> {
+        let providers = app_ids.iter().map(|app_id| provider(app_id)).collect();
+        ProviderBroker::pick_provider(
+            providers,
+            config,
+            ACK_CHALLENGE,
+            app_id.map(String::from).as_ref(),
+            |app_id| app_id == focused,
+        )
+        .map(|p| p.provider.app_id)
+    }
+
+    fn config(selection: Vec<ProviderSelection>, priorities: &[&str]) -> ProviderBrokerConfig {
+        ProviderBrokerConfig {
+            selection,
+            priorities: HashMap::from([(
+                ACK_CHALLENGE.to_owned(),
+                priorities.iter().map(|app_id| app_id.to_string()).collect(),
+            )]),
+            ..Default::default()
+        }
+    }
+
+    async fn invoke(
+        pst: &PlatformState,
+        capability: &str,
+    ) -> oneshot::Receiver<ProviderBrokerResponse> {
+        let (tx, rx) = oneshot::channel();
+        ProviderBroker::invoke_method(
+            pst,
+            ProviderBrokerRequest {
+                capability: capability.into(),
+                method: "challenge".into(),
+                caller: call_context("caller", "app"),
+                request: ProviderRequestPayload::Generic("challenge".into()),
+                tx,
+                app_id: None,
+            },
+        )
+        .await;
+        rx
+    }
+
+    async fn register(pst: &PlatformState, capability: &str, app_id: &str) {
+        ProviderBroker::register_provider(
+            pst,
+            capability.into(),
+            "challenge".into(),
+            "acknowledgechallenge.onRequestChallenge",
+            call_context(app_id, app_id),
+            ListenRequest { listen: true },
+        )
+        .await;
+    }
+
+    fn session_providers(pst: &PlatformState) -> Vec<String> {
+        pst.provider_broker_state
+            .active_sessions
+            .read()
+            .unwrap()
+            .values()
+            .map(|session| session.provider.provider.app_id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn select_most_recent_provider() {
+        let config = config(vec![ProviderSelection::MostRecent], &["a"]);
+        assert_eq!(pick(&["a", "b", "c"], &config, None, "a"), Some("c".into()));
+    }
+
+    #[test]
+    fn select_priority_provider() {
+        let config = config(vec![ProviderSelection::Priority], &["b", "a"]);
+        assert_eq!(pick(&["a", "b", "c"], &config, None, "c"), Some("b".into()));
+        // apps without a priority rank after every prioritized app
+        assert_eq!(pick(&["c", "a"], &config, None, "c"), Some("a".into()));
+    }
+
+    #[test]
+    fn select_focused_provider() {
+        let config = config(vec![ProviderSelection::Focused], &["c"]);
+        assert_eq!(pick(&["a", "b", "c"], &config, None, "b"), Some("b".into()));
+    }
+
+    #[test]
+    fn later_selections_break_ties() {
+        let config = config(
+            vec![
+                ProviderSelection::Priority,
+                ProviderSelection::Focused,
+                ProviderSelection::MostRecent,
+            ],
+            &["a"],
+        );
+        assert_eq!(pick(&["a", "b", "c"], &config, None, "c"), Some("a".into()));
+        assert_eq!(pick(&["b", "c", "d"], &config, None, "b"), Some("b".into()));
+        assert_eq!(pick(&["b", "c", "d"], &config, None, "x"), Some("d".into()));
+    }
+
+    #[test]
+    fn select_addressed_provider() {
+        let config = ProviderBrokerConfig::default();
+        assert_eq!(pick(&["a", "b"], &config, Some("a"), "b"), Some("a".into()));
+        assert_eq!(pick(&["a", "b"], &config, Some("x"), "b"), None);
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn failover_to_next_provider() {
+        let pst = platform_state().await;
+        register(&pst, ACK_CHALLENGE, "a").await;
+        register(&pst, ACK_CHALLENGE, "b").await;
+        let _rx = invoke(&pst, ACK_CHALLENGE).await;
+        assert_eq!(session_providers(&pst), vec!["b"]);
+
+        ProviderBroker::unregister_session(&pst, "b".into()).await;
+        assert_eq!(session_providers(&pst), vec!["a"]);
+        assert!(pst
+            .provider_broker_state
+            .request_queue
+            .read()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn failover_queues_without_provider() {
+        let pst = platform_state().await;
+        register(&pst, ACK_CHALLENGE, "a").await;
+        let _rx = invoke(&pst, ACK_CHALLENGE).await;
+        assert_eq!(session_providers(&pst), vec!["a"]);
+
+        ProviderBroker::unregister_provider(
+            &pst,
+            ACK_CHALLENGE.into(),
+            "challenge".into(),
+            call_context("a", "a"),
+        )
+        .await;
+        assert!(session_providers(&pst).is_empty());
+        assert_eq!(
+            pst.provider_broker_state
+                .request_queue
+                .read()
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // the queued request goes to the next provider registering
+        register(&pst, ACK_CHALLENGE, "b").await;
+        assert_eq!(session_providers(&pst), vec!["b"]);
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn failover_skips_abandoned_requests() {
+        let pst = platform_state().await;
+        register(&pst, ACK_CHALLENGE, "a").await;
+        register(&pst, ACK_CHALLENGE, "b").await;
+        drop(invoke(&pst, ACK_CHALLENGE).await);
+
+        ProviderBroker::unregister_session(&pst, "b".into()).await;
+        assert!(session_providers(&pst).is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use ripple_sdk::{
+    api::{
+        apps::{AppMethod, AppRequest, AppResponse, CloseReason},
+        firebolt::fb_secure_storage::{SecureStorageClearRequest, SecureStorageRequest},
+        storage_property::{
+            StorageProperty, NAMESPACE_CLOSED_CAPTIONS, NAMESPACE_DEVICE_NAME,
+            NAMESPACE_LOCALIZATION, NAMESPACE_PRIVACY, NAMESPACE_USER_GRANT,
+            NAMESPACE_VOICE_GUIDANCE,
+        },
+    },
+    log::{error, info},
+    tokio::sync::oneshot,
+};
+use serde::Serialize;
+
+use crate::{
+    processor::storage::storage_manager::StorageManager, service::user_grants::GrantState,
+    state::platform_state::PlatformState,
+};
+
+/// Storage namespaces holding the settings owned by Ripple.
+const RIPPLE_NAMESPACES: [&str; 6] = [
+    NAMESPACE_CLOSED_CAPTIONS,
+    NAMESPACE_PRIVACY,
+    NAMESPACE_DEVICE_NAME,
+    NAMESPACE_LOCALIZATION,
+    NAMESPACE_USER_GRANT,
+    NAMESPACE_VOICE_GUIDANCE,
+];
+
+/// Marks entries which apply to every app.
+const ALL_APPS: &str = "*";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ResetStore {
+    Apps,
+    Grants,
+    Permissions,
+    Settings,
+    SecureStorage,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetStep {
+    pub store: ResetStore,
+    pub cleared: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    pub dry_run: bool,
+    pub steps: Vec<ResetStep>,
+}
+
+/// Wipes the state Ripple keeps for apps, either for a single app or for the whole device.
+///
+/// The stores are cleared one after the other: loaded apps are closed first so they can't
+/// write while their data is removed, then grants, cached permissions, settings and secure
+/// storage. The requesting app and the default app stay loaded. A failing step is reported
+/// and the sequence carries on with the next one.
+pub struct DataReset;
+
+impl DataReset {
+    pub async fn reset(
+        state: &PlatformState,
+        caller: &str,
+        app_id: Option<String>,
+        dry_run: bool,
+    ) -> ResetReport {
+        info!(
+            "reset: app_id={} dry_run={}",
+            app_id.as_deref().unwrap_or(ALL_APPS),
+            dry_run
+        );
+        let app = app_id.as_deref();
+        let mut steps = vec![
+            Self::close_apps(state, caller, app, dry_run).await,
+            Self::clear_grants(state, app, dry_run).await,
+            Self::clear_permissions(state, app, dry_run),
+        ];
+        // Settings are device wide, an app has none of its own.
+        if app_id.is_none() {
+            steps.push(Self::reset_settings(state, dry_run).await);
+        }
+        steps.push(Self::clear_secure_storage(state, app, dry_run).await);
+
+        for step in &steps {
+            match &step.error {
+                Some(e) => error!("reset: {:?} failed {}", step.store, e),
+                None => info!("reset: {:?} cleared {:?}", step.store, step.cleared),
+            }
+        }
+        ResetReport {
+            app_id,
+            dry_run,
+            steps,
+        }
+    }
+
+    async fn close_apps(
+        state: &PlatformState,
+        caller: &str,
+        app_id: Option<&str>,
+        dry_run: bool,
+    ) -> ResetStep {
+        let default_app = state
+            .app_library_state
+            .get_default_app()
+            .map(|entry| entry.app_id);
+        let cleared: Vec<String> = state
+            .app_manager_state
+            .get_app_ids()
+            .into_iter()
+            .filter(|loaded| app_id.is_none() || app_id == Some(loaded.as_str()))
+            .filter(|loaded| loaded != caller && default_app.as_ref() != Some(loaded))
+            .collect();
+        let mut step = ResetStep::new(ResetStore::Apps, cleared);
+        if dry_run {
+            return step;
+        }
+        for loaded in &step.cleared {
+            let (resp_tx, resp_rx) = oneshot::channel::<AppResponse>();
+            let request = AppRequest::new(
+                AppMethod::Close(loaded.clone(), CloseReason::ResourceContention),
+                resp_tx,
+            );
+            let closed = match state.get_client().send_app_request(request) {
+                Ok(_) => matches!(resp_rx.await, Ok(Ok(_))),
+                Err(_) => false,
+            };
+            if !closed {
+                step.error = Some(format!("Unable to close {}", loaded));
+            }
+        }
+        step
+    }
+
+    async fn clear_grants(state: &PlatformState, app_id: Option<&str>, dry_run: bool) -> ResetStep {
+        let cleared = GrantState::clear(state, app_id, dry_run)
+            .await
+            .into_iter()
+            .map(|(app, entry)| {
+                format!(
+                    "{}:{}:{}",
+                    app.unwrap_or_else(|| String::from("device")),
+                    entry.capability,
+                    entry.role.as_string()
+                )
+            })
+            .collect();
+        ResetStep::new(ResetStore::Grants, cleared)
+    }
+
+    fn clear_permissions(state: &PlatformState, app_id: Option<&str>, dry_run: bool) -> ResetStep {
+        let cleared = state.cap_state.permitted_state.clear(app_id, dry_run);
+        ResetStep::new(ResetStore::Permissions, cleared)
+    }
+
+    async fn reset_settings(state: &PlatformState, dry_run: bool) -> ResetStep {
+        let mut step = ResetStep::new(ResetStore::Settings, Vec::new());
+        for namespace in RIPPLE_NAMESPACES {
+            let mut keys = match StorageManager::get_keys(state, namespace.to_owned()).await {
+                Ok(keys) => keys,
+                Err(e) => {
+                    step.error = Some(format!("Unable to list {} {:?}", namespace, e));
+                    continue;
+                }
+            };
+            // properties are reset one by one so their default values are emitted to the
+            // listening apps
+            for property in StorageProperty::ALL {
+                let data = property.as_data();
+                if data.namespace != namespace || !keys.iter().any(|key| key == data.key) {
+                    continue;
+                }
+                if !dry_run {
+                    if let Err(e) = StorageManager::reset(state, property.clone()).await {
+                        step.error = Some(format!("Unable to reset {:?} {:?}", property, e));
+                        continue;
+                    }
+                }
+                keys.retain(|key| key != data.key);
+                step.cleared.push(format!("{}.{}", namespace, data.key));
+            }
+            // Whatever is left isn't a storage property and has no listeners to tell.
+            if keys.is_empty() {
+                continue;
+            }
+            if !dry_run {
+                if let Err(e) = StorageManager::reset_namespace(state, namespace.to_owned()).await {
+                    step.error = Some(format!("Unable to reset {} {:?}", namespace, e));
+                    continue;
+                }
+            }
+            step.cleared
+                .extend(keys.into_iter().map(|key| format!("{}.{}", namespace, key)));
+        }
+        step
+    }
+
+    async fn clear_secure_storage(
+        state: &PlatformState,
+        app_id: Option<&str>,
+        dry_run: bool,
+    ) -> ResetStep {
+        // Secure storage can't be enumerated, the report names the app cleared.
+        let cleared = vec![app_id.unwrap_or(ALL_APPS).to_owned()];
+        let mut step = ResetStep::new(ResetStore::SecureStorage, cleared);
+        if dry_run {
+            return step;
+        }
+        let distributor_session = match state.session_state.get_account_session() {
+            Some(session) => session,
+            None => {
+                step.error = Some(String::from("Account session is not available"));
+                return step;
+            }
+        };
+        let request = SecureStorageRequest::Clear(SecureStorageClearRequest {
+            app_id: app_id.map(String::from),
+            scope: None,
+            distributor_session,
+        });
+        if let Err(e) = state.get_client().send_extn_request(request).await {
+            step.error = Some(format!("Unable to clear secure storage {:?}", e));
+        }
+        step
+    }
+}
+
+impl ResetStep {
+    fn new(store: ResetStore, cleared: Vec<String>) -> ResetStep {
+        ResetStep {
+            store,
+            cleared,
+            error: None,
+        }
+    }
+}
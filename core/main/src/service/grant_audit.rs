@@ -0,0 +1,131 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{
+    fs::{self, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    sync::{Arc, Mutex},
+};
+
+use ripple_sdk::{api::device::device_user_grants_data::GrantAuditRecord, log::error, serde_json};
+
+/// Append-only log of grant decisions persisted as one json record per line.
+///
+/// Once the log grows beyond the configured size it is rotated, so at most one previous
+/// log is kept on disk alongside the current one.
+#[derive(Debug, Clone)]
+pub struct GrantAuditLog {
+    path: String,
+    max_size_bytes: u64,
+    lock: Arc<Mutex<()>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GrantAuditFilter {
+    pub app_id: Option<String>,
+    pub capability: Option<String>,
+    /// Milliseconds since the unix epoch, inclusive
+    pub from: Option<u64>,
+    /// Milliseconds since the unix epoch, inclusive
+    pub to: Option<u64>,
+}
+
+impl GrantAuditFilter {
+    fn matches(&self, record: &GrantAuditRecord) -> bool {
+        if let Some(app_id) = &self.app_id {
+            if record.app_id.as_ref() != Some(app_id) {
+                return false;
+            }
+        }
+        if let Some(capability) = &self.capability {
+            if !record.capability.eq(capability) {
+                return false;
+            }
+        }
+        if let Some(from) = self.from {
+            if record.timestamp < from {
+                return false;
+            }
+        }
+        if let Some(to) = self.to {
+            if record.timestamp > to {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl GrantAuditLog {
+    pub fn new(saved_dir: &str, max_size_kb: u64) -> GrantAuditLog {
+        GrantAuditLog {
+            path: format!("{}grant_audit.log", saved_dir),
+            max_size_bytes: max_size_kb * 1024,
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    fn rotated_path(&self) -> String {
+        format!("{}.1", self.path)
+    }
+
+    pub fn record(&self, record: GrantAuditRecord) {
+        let line = match serde_json::to_string(&record) {
+            Ok(line) => line,
+            Err(e) => {
+                error!("Unable to serialize grant audit record {:?}", e);
+                return;
+            }
+        };
+        let _guard = self.lock.lock().unwrap();
+        if let Ok(metadata) = fs::metadata(&self.path) {
+            if metadata.len() + line.len() as u64 >= self.max_size_bytes {
+                if let Err(e) = fs::rename(&self.path, self.rotated_path()) {
+                    error!("Unable to rotate grant audit log {:?}", e);
+                }
+            }
+        }
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", line));
+        if let Err(e) = result {
+            error!("Unable to write grant audit record {:?}", e);
+        }
+    }
+
+    /// Returns the records matching the filter, oldest first.
+    pub fn query(&self, filter: &GrantAuditFilter) -> Vec<GrantAuditRecord> {
+        let _guard = self.lock.lock().unwrap();
+        let mut records = Vec::new();
+        for path in [self.rotated_path(), self.path.clone()] {
+            let file = match fs::File::open(&path) {
+                Ok(file) => file,
+                Err(_) => continue,
+            };
+            records.extend(
+                BufReader::new(file)
+                    .lines()
+                    .map_while(Result::ok)
+                    .filter_map(|line| serde_json::from_str::<GrantAuditRecord>(&line).ok())
+                    .filter(|record| filter.matches(record)),
+            );
+        }
+        records
+    }
+}
@@ -17,5 +17,7 @@
 
 pub mod apps;
 pub mod data_governance;
+pub mod data_reset;
 pub mod extn;
+pub mod grant_audit;
 pub mod user_grants;
@@ -18,7 +18,7 @@
 use std::{
     collections::{HashMap, HashSet},
     sync::{Arc, RwLock},
-    time::SystemTime,
+    time::{Duration, Instant, SystemTime},
 };
 
 use ripple_sdk::log::debug;
@@ -28,9 +28,9 @@ use ripple_sdk::{
         device::{
             device_peristence::SetBoolProperty,
             device_user_grants_data::{
-                AutoApplyPolicy, GrantActiveState, GrantEntry, GrantErrors, GrantLifespan,
-                GrantPolicy, GrantPrivacySetting, GrantScope, GrantStateModify, GrantStatus,
-                GrantStep,
+                AutoApplyPolicy, GrantActiveState, GrantAuditAction, GrantAuditRecord, GrantEntry,
+                GrantErrors, GrantLifespan, GrantPolicies, GrantPolicy, GrantPrivacySetting,
+                GrantScope, GrantStateModify, GrantStatus, GrantStep,
             },
         },
         firebolt::{
@@ -40,16 +40,17 @@ use ripple_sdk::{
             },
             fb_openrpc::{CapabilitySet, FireboltOpenRpcMethod},
             fb_pin::{PinChallengeConfiguration, PinChallengeRequest},
-            provider::{
-                Challenge, ChallengeRequestor, ProviderRequestPayload, ProviderResponsePayload,
-            },
+            provider::{Challenge, ChallengeRequestor, ProviderRequestPayload},
         },
         gateway::rpc_gateway_api::CallContext,
         manifest::device_manifest::DeviceManifest,
     },
-    framework::file_store::FileStore,
+    framework::{file_store::FileStore, store_cipher::StoreCipher},
     serde_json::Value,
-    tokio::sync::oneshot,
+    tokio::{
+        self,
+        sync::{oneshot, Notify},
+    },
     utils::error::RippleError,
 };
 use serde::Deserialize;
@@ -59,38 +60,267 @@ use crate::{
     state::{cap::cap_state::CapState, platform_state::PlatformState},
 };
 
-use super::apps::provider_broker::{ProviderBroker, ProviderBrokerRequest};
+use super::{
+    apps::provider_broker::{ProviderBroker, ProviderBrokerRequest, ProviderBrokerResponse},
+    grant_audit::{GrantAuditFilter, GrantAuditLog},
+};
 
 pub struct UserGrants {}
 
+/// Identifies a grant decision, concurrent requests for the same decision share a single
+/// user prompt.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GrantRequestKey {
+    app_id: String,
+    capability: String,
+    role: CapabilityRole,
+    scope: GrantScope,
+}
+
+impl GrantRequestKey {
+    fn new(app_id: &str, permission: &FireboltPermission, policy: &GrantPolicy) -> Self {
+        GrantRequestKey {
+            app_id: app_id.to_owned(),
+            capability: permission.cap.as_str(),
+            role: permission.role.clone(),
+            scope: policy.scope.clone(),
+        }
+    }
+}
+
+type GrantResultSender = oneshot::Sender<Result<(), DenyReasonWithCap>>;
+
+enum PendingGrant {
+    /// The caller resolves the decision.
+    Leader(PendingGrantGuard),
+    /// Another caller is resolving the decision.
+    Waiter(oneshot::Receiver<Result<(), DenyReasonWithCap>>),
+}
+
+/// Held by the caller resolving a pending decision. When the caller is dropped before it
+/// resolves, e.g. the app disconnected, the waiters are released and see the grant as
+/// ungranted so the next request prompts again.
+struct PendingGrantGuard {
+    grant_state: GrantState,
+    key: GrantRequestKey,
+    resolved: bool,
+}
+
+impl PendingGrantGuard {
+    fn resolve(mut self, result: &Result<(), DenyReasonWithCap>) {
+        self.grant_state.resolve_pending(&self.key, result);
+        self.resolved = true;
+    }
+}
+
+impl Drop for PendingGrantGuard {
+    fn drop(&mut self) {
+        if !self.resolved {
+            // Dropping the senders releases the waiters.
+            self.grant_state.pending.write().unwrap().remove(&self.key);
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GrantState {
     device_grants: Arc<RwLock<FileStore<HashSet<GrantEntry>>>>,
     grant_app_map: Arc<RwLock<FileStore<HashMap<String, HashSet<GrantEntry>>>>>,
     caps_needing_grants: Vec<String>,
+    pending: Arc<RwLock<HashMap<GrantRequestKey, Vec<GrantResultSender>>>>,
+    denials: Arc<RwLock<HashMap<GrantRequestKey, Instant>>>,
+    audit_log: GrantAuditLog,
+    expiry_notify: Arc<Notify>,
 }
 
 impl GrantState {
     pub fn new(manifest: DeviceManifest) -> GrantState {
         let saved_dir = manifest.clone().configuration.saved_dir;
-        let device_grant_path = format!("{}device_grants", saved_dir);
-        let dev_grant_store = if let Ok(v) = FileStore::load(device_grant_path.clone()) {
-            v
-        } else {
-            FileStore::new(device_grant_path.clone(), HashSet::new())
+        // encrypted stores stay in memory until LoadStoreCipherStep opens them with the key
+        let cipher = match manifest.configuration.store_encryption {
+            Some(_) => Err(RippleError::MissingInput),
+            None => Ok(None),
         };
+        let policies = manifest.get_grant_policies().unwrap_or_default();
+        let mut dev_grant_store = FileStore::open(
+            format!("{}device_grants", saved_dir),
+            HashSet::new(),
+            cipher.clone(),
+        );
+        let mut app_grant_store: FileStore<HashMap<String, HashSet<GrantEntry>>> =
+            FileStore::open(format!("{}app_grants", saved_dir), HashMap::new(), cipher);
 
-        let app_grant_path = format!("{}app_grants", saved_dir);
-        let app_grant_store = if let Ok(v) = FileStore::load(app_grant_path.clone()) {
-            v
-        } else {
-            FileStore::new(app_grant_path.clone(), HashMap::new())
-        };
+        Self::apply_policies(&policies, &mut dev_grant_store, &mut app_grant_store);
 
         GrantState {
             grant_app_map: Arc::new(RwLock::new(app_grant_store)),
             caps_needing_grants: manifest.get_caps_requiring_grant(),
             device_grants: Arc::new(RwLock::new(dev_grant_store)),
+            pending: Arc::new(RwLock::new(HashMap::new())),
+            denials: Arc::new(RwLock::new(HashMap::new())),
+            audit_log: GrantAuditLog::new(
+                &saved_dir,
+                manifest.capabilities.grant_audit_max_size_kb,
+            ),
+            expiry_notify: Arc::new(Notify::new()),
+        }
+    }
+
+    /// The ttl of the grant policy takes precedence over the one stored with a grant so that
+    /// manifest changes apply to existing grants as well.
+    fn apply_policies(
+        policies: &HashMap<String, GrantPolicies>,
+        dev_grant_store: &mut FileStore<HashSet<GrantEntry>>,
+        app_grant_store: &mut FileStore<HashMap<String, HashSet<GrantEntry>>>,
+    ) {
+        dev_grant_store.value = dev_grant_store
+            .value
+            .drain()
+            .map(|entry| Self::apply_policy_ttl(policies, entry))
+            .collect();
+        for entries in app_grant_store.value.values_mut() {
+            *entries = entries
+                .drain()
+                .map(|entry| Self::apply_policy_ttl(policies, entry))
+                .collect();
+        }
+    }
+
+    /// Loads the stores encrypted with the device key, the grants made before are kept.
+    pub fn load_encrypted(&self, manifest: &DeviceManifest, cipher: StoreCipher) {
+        let saved_dir = &manifest.configuration.saved_dir;
+        let policies = manifest.get_grant_policies().unwrap_or_default();
+        let mut dev_grant_store = FileStore::open(
+            format!("{}device_grants", saved_dir),
+            HashSet::new(),
+            Ok(Some(cipher.clone())),
+        );
+        let mut app_grant_store: FileStore<HashMap<String, HashSet<GrantEntry>>> = FileStore::open(
+            format!("{}app_grants", saved_dir),
+            HashMap::new(),
+            Ok(Some(cipher)),
+        );
+        Self::apply_policies(&policies, &mut dev_grant_store, &mut app_grant_store);
+
+        let mut device_grants = self.device_grants.write().unwrap();
+        dev_grant_store.value.extend(device_grants.value.drain());
+        *device_grants = dev_grant_store;
+        device_grants.sync();
+
+        let mut grant_app_map = self.grant_app_map.write().unwrap();
+        for (app_id, entries) in grant_app_map.value.drain() {
+            app_grant_store
+                .value
+                .entry(app_id)
+                .or_default()
+                .extend(entries);
+        }
+        *grant_app_map = app_grant_store;
+        grant_app_map.sync();
+    }
+
+    fn apply_policy_ttl(
+        policies: &HashMap<String, GrantPolicies>,
+        mut entry: GrantEntry,
+    ) -> GrantEntry {
+        if entry.lifespan == Some(GrantLifespan::Seconds) {
+            let permission = FireboltPermission {
+                cap: FireboltCap::Full(entry.capability.clone()),
+                role: entry.role.clone(),
+            };
+            if let Some(ttl) = policies
+                .get(&entry.capability)
+                .and_then(|p| p.get_policy(&permission))
+                .and_then(|p| p.lifespan_ttl)
+            {
+                entry.lifespan_ttl_in_secs = Some(ttl);
+            }
+        }
+        entry
+    }
+
+    fn audit_decision(
+        &self,
+        app_id: &str,
+        permission: &FireboltPermission,
+        grant_policy: &GrantPolicy,
+        is_allowed: bool,
+        step: String,
+    ) {
+        let mut record = GrantAuditRecord::new(
+            Some(app_id.to_owned()),
+            permission.cap.as_str(),
+            permission.role.clone(),
+            if is_allowed {
+                GrantAuditAction::Granted
+            } else {
+                GrantAuditAction::Denied
+            },
+        );
+        record.lifespan = Some(grant_policy.lifespan.clone());
+        record.step = Some(step);
+        self.audit_log.record(record);
+    }
+
+    fn audit_removed(&self, app_id: Option<String>, entries: &[GrantEntry]) {
+        for entry in entries {
+            self.audit_log.record(GrantAuditRecord::from_entry(
+                app_id.clone(),
+                entry,
+                GrantAuditAction::Expired,
+            ));
+        }
+    }
+
+    /// Returns the recorded grant decisions matching the filter, oldest first.
+    pub fn get_audit_records(&self, filter: &GrantAuditFilter) -> Vec<GrantAuditRecord> {
+        self.audit_log.query(filter)
+    }
+
+    /// Returns a receiver for the outcome when a decision for the key is already being
+    /// resolved, otherwise marks it as pending and returns a guard for the caller to resolve.
+    fn wait_for_pending(&self, key: &GrantRequestKey) -> PendingGrant {
+        let mut pending = self.pending.write().unwrap();
+        match pending.get_mut(key) {
+            Some(waiters) => {
+                let (tx, rx) = oneshot::channel();
+                waiters.push(tx);
+                PendingGrant::Waiter(rx)
+            }
+            None => {
+                pending.insert(key.clone(), Vec::new());
+                PendingGrant::Leader(PendingGrantGuard {
+                    grant_state: self.clone(),
+                    key: key.clone(),
+                    resolved: false,
+                })
+            }
+        }
+    }
+
+    fn resolve_pending(&self, key: &GrantRequestKey, result: &Result<(), DenyReasonWithCap>) {
+        let waiters = self.pending.write().unwrap().remove(key);
+        for waiter in waiters.unwrap_or_default() {
+            let _ = waiter.send(result.clone());
+        }
+    }
+
+    fn record_denial(&self, key: &GrantRequestKey) {
+        self.denials
+            .write()
+            .unwrap()
+            .insert(key.clone(), Instant::now());
+    }
+
+    fn is_cooling_down(&self, key: &GrantRequestKey, cooldown_secs: u64) -> bool {
+        let mut denials = self.denials.write().unwrap();
+        match denials.get(key) {
+            Some(denied_at) if denied_at.elapsed() < Duration::from_secs(cooldown_secs) => true,
+            Some(_) => {
+                denials.remove(key);
+                false
+            }
+            None => false,
         }
     }
 
@@ -117,6 +347,7 @@ impl GrantState {
         app_id: Option<String>, // None is for device
         new_entry: GrantEntry,
     ) {
+        let expires = new_entry.lifespan == Some(GrantLifespan::Seconds);
         if app_id.is_some() {
             let app_id = app_id.unwrap();
             let mut grant_state = self.grant_app_map.write().unwrap();
@@ -131,9 +362,13 @@ impl GrantState {
         } else {
             self.add_device_entry(new_entry)
         }
+        if expires {
+            // reschedule once the grant is stored in case it expires before the pending ones
+            self.expiry_notify.notify_one();
+        }
     }
 
-    pub fn custom_delete_entries<F>(&self, app_id: String, restrict_function: F) -> bool
+    pub fn custom_delete_entries<F>(&self, app_id: String, mut restrict_function: F) -> bool
     where
         F: FnMut(&GrantEntry) -> bool,
     {
@@ -143,15 +378,165 @@ impl GrantState {
             Some(entries) => entries,
             None => return false,
         };
-        let prev_len = entries.len();
+        let removed: Vec<GrantEntry> = entries
+            .iter()
+            .filter(|entry| !restrict_function(entry))
+            .cloned()
+            .collect();
         entries.retain(restrict_function);
-        if entries.len() < prev_len {
+        if !removed.is_empty() {
             deleted = true;
         }
         grant_state.sync();
+        self.audit_removed(Some(app_id), &removed);
+        deleted
+    }
+
+    /// Removes the app and device grants with the given lifespan, returning the removed entries.
+    fn delete_entries_with_lifespan(&self, lifespan: &GrantLifespan) -> Vec<GrantEntry> {
+        let mut deleted = Vec::new();
+        let has_lifespan = |entry: &GrantEntry| entry.lifespan.as_ref() == Some(lifespan);
+        {
+            let mut grant_state = self.grant_app_map.write().unwrap();
+            for (app_id, entries) in grant_state.value.iter_mut() {
+                let removed: Vec<GrantEntry> = entries
+                    .iter()
+                    .filter(|e| has_lifespan(e))
+                    .cloned()
+                    .collect();
+                entries.retain(|e| !has_lifespan(e));
+                self.audit_removed(Some(app_id.clone()), &removed);
+                deleted.extend(removed);
+            }
+            grant_state.sync();
+        }
+        let mut device_grants = self.device_grants.write().unwrap();
+        let removed: Vec<GrantEntry> = device_grants
+            .value
+            .iter()
+            .filter(|e| has_lifespan(e))
+            .cloned()
+            .collect();
+        device_grants.value.retain(|e| !has_lifespan(e));
+        device_grants.sync();
+        self.audit_removed(None, &removed);
+        deleted.extend(removed);
         deleted
     }
 
+    /// Removes the grants of the app, or the grants of every app and the device grants,
+    /// notifying `capabilities.onRevoked` listeners. Nothing is removed on a dry run, the
+    /// entries which would be are returned with their app id, `None` for device grants.
+    pub async fn clear(
+        platform_state: &PlatformState,
+        app_id: Option<&str>,
+        dry_run: bool,
+    ) -> Vec<(Option<String>, GrantEntry)> {
+        let grant_state = &platform_state.cap_state.grant_state;
+        let mut cleared = Vec::new();
+        {
+            let mut grant_app_map = grant_state.grant_app_map.write().unwrap();
+            for (app, entries) in grant_app_map.value.iter() {
+                if app_id.is_none() || app_id == Some(app.as_str()) {
+                    cleared.extend(entries.iter().map(|e| (Some(app.clone()), e.clone())));
+                }
+            }
+            if !dry_run {
+                grant_app_map
+                    .value
+                    .retain(|app, _| app_id.is_some() && app_id != Some(app.as_str()));
+                grant_app_map.sync();
+            }
+        }
+        if app_id.is_none() {
+            let mut device_grants = grant_state.device_grants.write().unwrap();
+            cleared.extend(device_grants.value.iter().map(|e| (None, e.clone())));
+            if !dry_run {
+                device_grants.value.clear();
+                device_grants.sync();
+            }
+        }
+        if dry_run {
+            return cleared;
+        }
+        for (app, entry) in &cleared {
+            grant_state.audit_log.record(GrantAuditRecord::from_entry(
+                app.clone(),
+                entry,
+                GrantAuditAction::Cleared,
+            ));
+        }
+        Self::notify_revoked(
+            platform_state,
+            cleared.iter().map(|(_, entry)| entry.clone()).collect(),
+        )
+        .await;
+        cleared
+    }
+
+    /// Clears the grants which only last while the device is powered on, notifying
+    /// `capabilities.onRevoked` listeners.
+    pub async fn clear_power_active_grants(platform_state: &PlatformState) {
+        let deleted = platform_state
+            .cap_state
+            .grant_state
+            .delete_entries_with_lifespan(&GrantLifespan::PowerActive);
+        Self::notify_revoked(platform_state, deleted).await;
+    }
+
+    /// Removes the `Seconds` grants as they expire, notifying `capabilities.onRevoked`
+    /// listeners. Runs for the lifetime of Ripple.
+    pub async fn run_expiry_scheduler(platform_state: PlatformState) {
+        let grant_state = platform_state.cap_state.grant_state.clone();
+        loop {
+            let expired = grant_state.take_expired_entries();
+            Self::notify_revoked(&platform_state, expired).await;
+            let notified = grant_state.expiry_notify.notified();
+            match grant_state.next_expiry() {
+                Some(wait) => {
+                    let _ = tokio::time::timeout(wait, notified).await;
+                }
+                None => notified.await,
+            }
+        }
+    }
+
+    /// Time until the earliest `Seconds` grant expires.
+    fn next_expiry(&self) -> Option<Duration> {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap();
+        let app_grants = self.grant_app_map.read().unwrap();
+        let device_grants = self.device_grants.read().unwrap();
+        app_grants
+            .value
+            .values()
+            .flatten()
+            .chain(device_grants.value.iter())
+            .filter_map(|entry| entry.expires_at())
+            .min()
+            // has_expired only holds once the ttl has been exceeded
+            .map(|expires_at| expires_at.saturating_sub(now) + Duration::from_millis(1))
+    }
+
+    async fn notify_revoked(platform_state: &PlatformState, deleted: Vec<GrantEntry>) {
+        let mut revoked = HashSet::new();
+        for entry in deleted {
+            if entry.status == Some(GrantStatus::Allowed)
+                && revoked.insert((entry.capability.clone(), entry.role.as_string()))
+            {
+                debug!("Grant revoked for {}", entry.capability);
+                CapState::emit(
+                    platform_state,
+                    CapEvent::OnRevoked,
+                    FireboltCap::Full(entry.capability),
+                    Some(entry.role),
+                )
+                .await;
+            }
+        }
+    }
+
     pub fn delete_expired_entries_for_app(
         &self,
         app_id: String, // None is for device
@@ -162,26 +547,56 @@ impl GrantState {
             Some(entries) => entries,
             None => return false,
         };
-        let prev_len = entries.len();
+        let removed: Vec<GrantEntry> = entries
+            .iter()
+            .filter(|entry| entry.has_expired())
+            .cloned()
+            .collect();
         entries.retain(|entry| !entry.has_expired());
-        if entries.len() < prev_len {
+        if !removed.is_empty() {
             deleted = true;
         }
         grant_state.sync();
+        self.audit_removed(Some(app_id), &removed);
         deleted
     }
 
     pub fn delete_all_expired_entries(&self) -> bool {
-        let mut deleted = false;
-        let mut grant_state = self.grant_app_map.write().unwrap();
-        for (_, entries) in grant_state.value.iter_mut() {
-            let prev_len = entries.len();
-            entries.retain(|entry| !entry.has_expired());
-            if entries.len() < prev_len {
-                deleted = true;
+        !self.take_expired_entries().is_empty()
+    }
+
+    /// Removes the expired app and device grants, returning the removed entries.
+    fn take_expired_entries(&self) -> Vec<GrantEntry> {
+        let mut deleted = Vec::new();
+        {
+            let mut grant_state = self.grant_app_map.write().unwrap();
+            for (app_id, entries) in grant_state.value.iter_mut() {
+                let removed: Vec<GrantEntry> = entries
+                    .iter()
+                    .filter(|entry| entry.has_expired())
+                    .cloned()
+                    .collect();
+                entries.retain(|entry| !entry.has_expired());
+                self.audit_removed(Some(app_id.clone()), &removed);
+                deleted.extend(removed);
+            }
+            if !deleted.is_empty() {
+                grant_state.sync();
             }
         }
-        grant_state.sync();
+        let mut device_grants = self.device_grants.write().unwrap();
+        let removed: Vec<GrantEntry> = device_grants
+            .value
+            .iter()
+            .filter(|entry| entry.has_expired())
+            .cloned()
+            .collect();
+        if !removed.is_empty() {
+            device_grants.value.retain(|entry| !entry.has_expired());
+            device_grants.sync();
+            self.audit_removed(None, &removed);
+            deleted.extend(removed);
+        }
         deleted
     }
 
@@ -473,27 +888,32 @@ impl GrantState {
                             .unwrap(),
                     };
 
-                    match modify_operation {
+                    let (action, step) = match modify_operation {
                         GrantStateModify::Grant => {
                             // insert the allowed GrantEntry
                             new_entry.status = Some(GrantStatus::Allowed);
                             entry_modified = true;
+                            (GrantAuditAction::Granted, "usergrants.grant")
                         }
                         GrantStateModify::Deny => {
                             // insert the denied GrantEntry
                             new_entry.status = Some(GrantStatus::Denied);
                             entry_modified = true;
+                            (GrantAuditAction::Denied, "usergrants.deny")
                         }
                         GrantStateModify::Clear => {
                             // No op as the entry is already removed.
                             entry_modified = true;
+                            (GrantAuditAction::Cleared, "usergrants.clear")
                         }
-                    }
+                    };
 
-                    platform_state
-                        .cap_state
-                        .grant_state
-                        .update_grant_entry(app_id, new_entry);
+                    let grant_state = &platform_state.cap_state.grant_state;
+                    let mut record =
+                        GrantAuditRecord::from_entry(app_id.clone(), &new_entry, action);
+                    record.step = Some(step.to_owned());
+                    grant_state.audit_log.record(record);
+                    grant_state.update_grant_entry(app_id, new_entry);
                 }
             }
         }
@@ -560,6 +980,27 @@ impl GrantPolicyEnforcer {
                             reason: DenyReason::Disabled,
                         });
                     }
+                    let grant_state = &platform_state.cap_state.grant_state;
+                    let key = GrantRequestKey::new(&call_context.app_id, permission, &policy);
+                    if let Some(cooldown_secs) = policy.denial_cooldown_secs {
+                        if grant_state.is_cooling_down(&key, cooldown_secs) {
+                            debug!("Recently denied, not prompting again");
+                            return Err(DenyReasonWithCap {
+                                caps: vec![permission.clone().cap],
+                                reason: DenyReason::GrantDenied,
+                            });
+                        }
+                    }
+                    let pending = match grant_state.wait_for_pending(&key) {
+                        PendingGrant::Leader(pending) => pending,
+                        PendingGrant::Waiter(rx) => {
+                            debug!("Awaiting grant decision already in progress");
+                            return rx.await.unwrap_or(Err(DenyReasonWithCap {
+                                caps: vec![permission.clone().cap],
+                                reason: DenyReason::Ungranted,
+                            }));
+                        }
+                    };
                     let result = GrantPolicyEnforcer::execute(
                         platform_state,
                         call_context,
@@ -567,12 +1008,13 @@ impl GrantPolicyEnforcer {
                         &policy,
                     )
                     .await;
-                    platform_state.cap_state.grant_state.update(
-                        permission,
-                        &policy,
-                        result.is_ok(),
-                        &call_context.app_id,
-                    );
+                    grant_state.update(permission, &policy, result.is_ok(), &call_context.app_id);
+                    if let Err(e) = &result {
+                        if e.reason == DenyReason::GrantDenied {
+                            grant_state.record_denial(&key);
+                        }
+                    }
+                    pending.resolve(&result);
                     return result;
                 } else {
                     debug!("We dont have a policy for role");
@@ -768,9 +1210,17 @@ impl GrantPolicyEnforcer {
                         reason: DenyReason::GrantDenied,
                     });
                 } else {
-                    match GrantStepExecutor::execute(step, platform_state, call_ctx, permission)
-                        .await
-                    {
+                    let result =
+                        GrantStepExecutor::execute(step, platform_state, call_ctx, permission)
+                            .await;
+                    platform_state.cap_state.grant_state.audit_decision(
+                        &call_ctx.app_id,
+                        permission,
+                        policy,
+                        result.is_ok(),
+                        step.capability.clone(),
+                    );
+                    match result {
                         Ok(_) => {
                             CapState::emit(
                                 platform_state,
@@ -805,16 +1255,21 @@ impl GrantPolicyEnforcer {
         permission: &FireboltPermission,
         policy: &GrantPolicy,
     ) -> Result<(), DenyReasonWithCap> {
-        if policy.privacy_setting.is_some()
-            && policy.privacy_setting.as_ref().unwrap().auto_apply_policy != AutoApplyPolicy::Never
+        if let Some(privacy_setting) = policy
+            .privacy_setting
+            .as_ref()
+            .filter(|x| x.auto_apply_policy != AutoApplyPolicy::Never)
         {
-            if let Some(priv_sett_response) = Self::evaluate_privacy_settings(
-                platform_state,
-                &policy.privacy_setting.as_ref().unwrap(),
-                call_ctx,
-            )
-            .await
+            if let Some(priv_sett_response) =
+                Self::evaluate_privacy_settings(platform_state, privacy_setting, call_ctx).await
             {
+                platform_state.cap_state.grant_state.audit_decision(
+                    &call_ctx.app_id,
+                    permission,
+                    policy,
+                    priv_sett_response.is_ok(),
+                    privacy_setting.property.clone(),
+                );
                 return priv_sett_response.map_err(|err| DenyReasonWithCap {
                     reason: err,
                     caps: vec![permission.cap.clone()],
@@ -895,7 +1350,7 @@ impl GrantStepExecutor {
         param: &Option<Value>,
         permission: &FireboltPermission,
     ) -> Result<(), DenyReasonWithCap> {
-        let (session_tx, session_rx) = oneshot::channel::<ProviderResponsePayload>();
+        let (session_tx, session_rx) = oneshot::channel::<ProviderBrokerResponse>();
         let p_cap = cap.clone();
         /*
          * We have a concrete struct defined for ack challenge and pin challenge hence handling them separately. If any new
@@ -970,7 +1425,7 @@ impl GrantStepExecutor {
         let result = if let Some(pr_msg) = pr_msg_opt {
             ProviderBroker::invoke_method(&platform_state.clone(), pr_msg).await;
             match session_rx.await {
-                Ok(result) => match result.as_challenge_response() {
+                Ok(Ok(result)) => match result.as_challenge_response() {
                     Some(res) => match res.granted {
                         true => {
                             debug!("returning ok from invoke_capability");
@@ -986,7 +1441,7 @@ impl GrantStepExecutor {
                         Err(DenyReason::Ungranted)
                     }
                 },
-                Err(_) => {
+                _ => {
                     debug!("Receive error in channel");
                     Err(DenyReason::Ungranted)
                 }
@@ -1010,3 +1465,205 @@ impl GrantStepExecutor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        service::extn::ripple_client::RippleClient, state::bootstrap_state::ChannelsState,
+    };
+    use ripple_sdk::{api::manifest::extn_manifest::ExtnManifest, serde_json};
+
+    const POSTAL_CODE: &str = "xrn:firebolt:capability:localization:postal-code";
+    const LOCALITY: &str = "xrn:firebolt:capability:localization:locality";
+    const COUNTRY_CODE: &str = "xrn:firebolt:capability:localization:country-code";
+
+    fn manifest() -> DeviceManifest {
+        let mut manifest: serde_json::Value = serde_json::from_str(include_str!(
+            "../../../../examples/manifest/device-manifest-example.json"
+        ))
+        .unwrap();
+        let saved_dir = std::env::temp_dir().join(format!(
+            "user_grants_{}_{:?}/",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let _ = std::fs::remove_dir_all(&saved_dir);
+        std::fs::create_dir_all(&saved_dir).unwrap();
+        manifest["configuration"]["saved_dir"] = saved_dir.to_str().unwrap().into();
+        let (_, manifest) = DeviceManifest::load_from_content(manifest.to_string()).unwrap();
+        manifest
+    }
+
+    fn grant_state() -> GrantState {
+        GrantState::new(manifest())
+    }
+
+    fn platform_state() -> PlatformState {
+        let (_, extn_manifest) = ExtnManifest::load_from_content(
+            include_str!("../../../../examples/manifest/extn-manifest-example.json").to_owned(),
+        )
+        .unwrap();
+        let client = RippleClient::new(ChannelsState::new());
+        PlatformState::new(extn_manifest, manifest(), client, Vec::new())
+    }
+
+    /// Allowed `Seconds` grant modified `age` ago.
+    fn seconds_grant(capability: &str, ttl: u64, age: Duration) -> GrantEntry {
+        let mut entry = GrantEntry::get(CapabilityRole::Use, capability.to_owned());
+        entry.status = Some(GrantStatus::Allowed);
+        entry.lifespan = Some(GrantLifespan::Seconds);
+        entry.lifespan_ttl_in_secs = Some(ttl);
+        entry.last_modified_time -= age;
+        entry
+    }
+
+    fn has_app_grant(grant_state: &GrantState, capability: &str) -> bool {
+        let entry = GrantEntry::get(CapabilityRole::Use, capability.to_owned());
+        grant_state
+            .grant_app_map
+            .read()
+            .unwrap()
+            .value
+            .get("app")
+            .is_some_and(|entries| entries.contains(&entry))
+    }
+
+    fn key() -> GrantRequestKey {
+        GrantRequestKey {
+            app_id: String::from("app"),
+            capability: String::from("xrn:firebolt:capability:localization:postal-code"),
+            role: CapabilityRole::Use,
+            scope: GrantScope::App,
+        }
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn cancelled_leader_releases_waiters() {
+        let grant_state = grant_state();
+        let pending = match grant_state.wait_for_pending(&key()) {
+            PendingGrant::Leader(pending) => pending,
+            PendingGrant::Waiter(_) => panic!("first request should lead"),
+        };
+        let leader = tokio::spawn(async move {
+            let _pending = pending;
+            std::future::pending::<()>().await
+        });
+        let waiter = match grant_state.wait_for_pending(&key()) {
+            PendingGrant::Waiter(rx) => rx,
+            PendingGrant::Leader(_) => panic!("second request should wait"),
+        };
+
+        leader.abort();
+        assert!(waiter.await.is_err());
+        assert!(matches!(
+            grant_state.wait_for_pending(&key()),
+            PendingGrant::Leader(_)
+        ));
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn resolved_leader_notifies_waiters() {
+        let grant_state = grant_state();
+        let pending = match grant_state.wait_for_pending(&key()) {
+            PendingGrant::Leader(pending) => pending,
+            PendingGrant::Waiter(_) => panic!("first request should lead"),
+        };
+        let waiter = match grant_state.wait_for_pending(&key()) {
+            PendingGrant::Waiter(rx) => rx,
+            PendingGrant::Leader(_) => panic!("second request should wait"),
+        };
+
+        pending.resolve(&Ok(()));
+        assert!(matches!(waiter.await, Ok(Ok(()))));
+        assert!(grant_state.pending.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn policy_ttl_overrides_stored_ttl() {
+        let policy = GrantPolicy {
+            lifespan: GrantLifespan::Seconds,
+            lifespan_ttl: Some(10),
+            ..Default::default()
+        };
+        let policies = HashMap::from([(
+            POSTAL_CODE.to_owned(),
+            GrantPolicies {
+                _use: Some(policy),
+                manage: None,
+                provide: None,
+            },
+        )]);
+
+        let entry = GrantState::apply_policy_ttl(
+            &policies,
+            seconds_grant(POSTAL_CODE, 100, Duration::ZERO),
+        );
+        assert_eq!(entry.lifespan_ttl_in_secs, Some(10));
+
+        let entry =
+            GrantState::apply_policy_ttl(&policies, seconds_grant(LOCALITY, 100, Duration::ZERO));
+        assert_eq!(entry.lifespan_ttl_in_secs, Some(100));
+
+        let mut forever = seconds_grant(POSTAL_CODE, 100, Duration::ZERO);
+        forever.lifespan = Some(GrantLifespan::Forever);
+        let entry = GrantState::apply_policy_ttl(&policies, forever);
+        assert_eq!(entry.lifespan_ttl_in_secs, Some(100));
+    }
+
+    #[test]
+    fn next_expiry_is_the_earliest_grant() {
+        let grant_state = grant_state();
+        assert!(grant_state.next_expiry().is_none());
+
+        let mut power_active = GrantEntry::get(CapabilityRole::Use, COUNTRY_CODE.to_owned());
+        power_active.lifespan = Some(GrantLifespan::PowerActive);
+        grant_state.update_grant_entry(Some("app".to_owned()), power_active);
+        assert!(grant_state.next_expiry().is_none());
+
+        grant_state.update_grant_entry(
+            Some("app".to_owned()),
+            seconds_grant(POSTAL_CODE, 60, Duration::ZERO),
+        );
+        grant_state.update_grant_entry(None, seconds_grant(LOCALITY, 60, Duration::from_secs(30)));
+        let wait = grant_state.next_expiry().unwrap();
+        assert!(wait > Duration::from_secs(29) && wait <= Duration::from_millis(30_001));
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio", start_paused = true)]
+    async fn expiry_scheduler_wakes_for_new_and_due_grants() {
+        let platform_state = platform_state();
+        let grant_state = platform_state.cap_state.grant_state.clone();
+        tokio::spawn(GrantState::run_expiry_scheduler(platform_state));
+        tokio::time::sleep(Duration::from_millis(1)).await;
+
+        // the scheduler waits without a timer while there is no grant to expire
+        grant_state.update_grant_entry(
+            Some("app".to_owned()),
+            seconds_grant(POSTAL_CODE, 1, Duration::from_secs(2)),
+        );
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert!(!has_app_grant(&grant_state, POSTAL_CODE));
+
+        grant_state.update_grant_entry(
+            Some("app".to_owned()),
+            seconds_grant(LOCALITY, 3600, Duration::ZERO),
+        );
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        // stored without notifying, only removed once the scheduler wakes for the first grant
+        grant_state
+            .grant_app_map
+            .write()
+            .unwrap()
+            .value
+            .get_mut("app")
+            .unwrap()
+            .insert(seconds_grant(COUNTRY_CODE, 1, Duration::from_secs(2)));
+
+        tokio::time::sleep(Duration::from_secs(1800)).await;
+        assert!(has_app_grant(&grant_state, COUNTRY_CODE));
+        tokio::time::sleep(Duration::from_secs(1801)).await;
+        assert!(!has_app_grant(&grant_state, COUNTRY_CODE));
+        assert!(has_app_grant(&grant_state, LOCALITY));
+    }
+}
@@ -21,6 +21,7 @@ use std::{
 };
 
 use ripple_sdk::api::{
+    device::device_events::DeviceCondition,
     firebolt::fb_capabilities::{DenyReason, DenyReasonWithCap, FireboltCap},
     manifest::device_manifest::DeviceManifest,
 };
@@ -30,6 +31,8 @@ pub struct GenericCapState {
     supported: Arc<RwLock<HashSet<String>>>,
     // it consumes less memory and operations to store not_available vs available
     not_available: Arc<RwLock<HashSet<String>>>,
+    // device conditions reported as not holding, unknown conditions are assumed to hold
+    unmet_conditions: Arc<RwLock<HashSet<DeviceCondition>>>,
 }
 
 impl GenericCapState {
@@ -60,6 +63,19 @@ impl GenericCapState {
         }
     }
 
+    pub fn set_condition(&self, condition: DeviceCondition, is_met: bool) {
+        let mut unmet_conditions = self.unmet_conditions.write().unwrap();
+        if is_met {
+            unmet_conditions.remove(&condition);
+        } else {
+            unmet_conditions.insert(condition);
+        }
+    }
+
+    pub fn is_condition_met(&self, condition: &DeviceCondition) -> bool {
+        !self.unmet_conditions.read().unwrap().contains(condition)
+    }
+
     pub fn check_for_processor(&self, request: Vec<String>) -> HashMap<String, bool> {
         let supported = self.supported.read().unwrap();
         let mut result = HashMap::new();
@@ -16,64 +16,182 @@
 //
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, RwLock},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
+use futures::future::{BoxFuture, FutureExt};
 use ripple_sdk::{
     api::{
         distributor::distributor_permissions::PermissionRequest,
         firebolt::{
-            fb_capabilities::{DenyReasonWithCap, FireboltPermission, RoleInfo},
+            fb_capabilities::{
+                CapEvent, DenyReasonWithCap, FireboltCap, FireboltPermission, PermissionRules,
+                RoleInfo,
+            },
             fb_openrpc::CapabilitySet,
         },
-        manifest::device_manifest::DeviceManifest,
+        manifest::{app_library::AppLibrary, device_manifest::DeviceManifest},
     },
-    framework::{file_store::FileStore, RippleResponse},
-    log::info,
+    framework::{
+        file_store::{FileStore, FileStoreMigration},
+        store_cipher::StoreCipher,
+        RippleResponse,
+    },
+    log::{error, info},
+    serde_json::{json, Value},
+    tokio,
     utils::error::RippleError,
 };
+use serde::{Deserialize, Serialize};
+
+use crate::state::{cap::cap_state::CapState, platform_state::PlatformState};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionEntry {
+    pub permissions: Vec<FireboltPermission>,
+    /// Seconds since epoch when the permissions were fetched.
+    pub fetched_at: u64,
+}
+
+impl PermissionEntry {
+    fn new(permissions: Vec<FireboltPermission>) -> PermissionEntry {
+        PermissionEntry {
+            permissions,
+            fetched_at: now_secs(),
+        }
+    }
+}
 
-use crate::state::platform_state::PlatformState;
+/// Version 0 stored the permissions of each app without the time they were fetched.
+const PERMISSIONS_MIGRATIONS: &[FileStoreMigration] = &[migrate_timestamped_permissions];
+
+fn migrate_timestamped_permissions(value: Value) -> Result<Value, RippleError> {
+    match value {
+        Value::Object(apps) => Ok(Value::Object(
+            apps.into_iter()
+                .map(|(app_id, permissions)| {
+                    // A fetch time of 0 marks the permissions stale, refreshing them on next use
+                    (app_id, json!({"permissions": permissions, "fetched_at": 0}))
+                })
+                .collect(),
+        )),
+        _ => Err(RippleError::ParseError),
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct PermittedState {
-    permitted: Arc<RwLock<FileStore<HashMap<String, Vec<FireboltPermission>>>>>,
+    permitted: Arc<RwLock<FileStore<HashMap<String, PermissionEntry>>>>,
+    ttl_secs: u64,
+    rules: PermissionRules,
+    refreshing: Arc<RwLock<HashSet<String>>>,
 }
 
 impl PermittedState {
     pub fn new(manifest: DeviceManifest) -> PermittedState {
-        let path = get_permissions_path(manifest.configuration.saved_dir);
-        let store = if let Ok(v) = FileStore::load(path.clone()) {
-            v
-        } else {
-            FileStore::new(path.clone(), HashMap::new())
+        // opened by LoadStoreCipherStep when the store is encrypted
+        let cipher = match manifest.configuration.store_encryption {
+            Some(_) => Err(RippleError::MissingInput),
+            None => Ok(None),
         };
+        let path = get_permissions_path(manifest.configuration.saved_dir);
+        let store = FileStore::open_versioned(path, HashMap::new(), PERMISSIONS_MIGRATIONS, cipher);
 
         PermittedState {
             permitted: Arc::new(RwLock::new(store)),
+            ttl_secs: manifest.capabilities.permissions_ttl_secs,
+            rules: manifest.capabilities.permission_rules.clone(),
+            refreshing: Arc::new(RwLock::new(HashSet::new())),
         }
     }
 
+    /// Loads the store encrypted with the device key, the permissions fetched before are kept.
+    pub fn load_encrypted(&self, manifest: &DeviceManifest, cipher: StoreCipher) {
+        let mut store = FileStore::open_versioned(
+            get_permissions_path(manifest.configuration.saved_dir.clone()),
+            HashMap::new(),
+            PERMISSIONS_MIGRATIONS,
+            Ok(Some(cipher)),
+        );
+        let mut permitted = self.permitted.write().unwrap();
+        store.value.extend(permitted.value.drain());
+        *permitted = store;
+        permitted.sync();
+    }
+
     fn ingest(&mut self, extend_perms: HashMap<String, Vec<FireboltPermission>>) {
         let mut perms = self.permitted.write().unwrap();
-        perms.value.extend(extend_perms);
+        perms.value.extend(
+            extend_perms
+                .into_iter()
+                .map(|(app_id, permissions)| (app_id, PermissionEntry::new(permissions))),
+        );
         perms.sync();
     }
 
     fn get_all_permissions(&self) -> HashMap<String, Vec<FireboltPermission>> {
-        self.permitted.read().unwrap().clone().value
+        self.permitted
+            .read()
+            .unwrap()
+            .value
+            .iter()
+            .map(|(app_id, entry)| (app_id.clone(), entry.permissions.clone()))
+            .collect()
+    }
+
+    fn get_cached_apps(&self) -> Vec<String> {
+        self.permitted
+            .read()
+            .unwrap()
+            .value
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// Drops the cached permissions of the app, or of every app, returning the apps which
+    /// had permissions cached. Nothing is removed on a dry run.
+    pub fn clear(&self, app_id: Option<&str>, dry_run: bool) -> Vec<String> {
+        let mut perms = self.permitted.write().unwrap();
+        let cleared: Vec<String> = perms
+            .value
+            .keys()
+            .filter(|cached| app_id.is_none() || app_id == Some(cached.as_str()))
+            .cloned()
+            .collect();
+        if !dry_run && !cleared.is_empty() {
+            perms.value.retain(|cached, _| !cleared.contains(cached));
+            perms.sync();
+        }
+        cleared
+    }
+
+    fn is_stale(&self, app_id: &str) -> bool {
+        match self.permitted.read().unwrap().value.get(app_id) {
+            Some(entry) => now_secs().saturating_sub(entry.fetched_at) >= self.ttl_secs,
+            None => true,
+        }
+    }
+
+    /// Marks a refresh for the app as in flight, returns false if one already is.
+    fn start_refresh(&self, app_id: &str) -> bool {
+        self.refreshing.write().unwrap().insert(app_id.to_owned())
+    }
+
+    fn end_refresh(&self, app_id: &str) {
+        self.refreshing.write().unwrap().remove(app_id);
     }
 
     pub fn check_cap_role(&self, app_id: &str, role_info: RoleInfo) -> Result<bool, RippleError> {
         if let Some(role) = role_info.role {
             if let Some(perms) = self.get_all_permissions().get(app_id) {
-                for perm in perms {
-                    if perm.cap.as_str() == role_info.capability && perm.role == role {
-                        return Ok(true);
-                    }
-                }
-                return Ok(false);
+                let requested = FireboltPermission {
+                    cap: FireboltCap::Full(role_info.capability),
+                    role,
+                };
+                return Ok(self.rules.is_permitted(perms, &requested));
             } else {
                 // Not cached prior
                 return Err(RippleError::InvalidAccess);
@@ -101,6 +219,13 @@ impl PermittedState {
     }
 }
 
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
 fn get_permissions_path(saved_dir: String) -> String {
     format!("{}/{}", saved_dir, "app_perms")
 }
@@ -109,35 +234,106 @@ pub struct PermissionHandler;
 
 impl PermissionHandler {
     pub async fn fetch_and_store(state: PlatformState, app_id: String) -> RippleResponse {
-        if state
-            .cap_state
-            .permitted_state
-            .get_app_permissions(&app_id)
-            .is_some()
-        {
+        // Apps with an embedded manifest declare their own permissions
+        if let Some(manifest) = AppLibrary::get_manifest(&state.app_library_state, &app_id) {
+            let mut map = HashMap::new();
+            map.insert(app_id.clone(), manifest.get_permissions());
+            let mut permitted_state = state.cap_state.permitted_state.clone();
+            permitted_state.ingest(map);
+            info!("Permissions seeded from manifest for {}", app_id);
+            return Ok(());
+        }
+        let permitted_state = &state.cap_state.permitted_state;
+        if permitted_state.get_app_permissions(&app_id).is_some() {
+            // Serve the cached permissions while stale ones are refreshed
+            if permitted_state.is_stale(&app_id) {
+                let state_c = state.clone();
+                tokio::spawn(async move {
+                    if Self::refresh_boxed(state_c, app_id.clone()).await.is_err() {
+                        error!("Couldnt refresh permissions for app {}", app_id)
+                    }
+                });
+            }
             return Ok(());
         }
+        Self::store(&state, &app_id).await.map(|_| ())
+    }
+
+    /// Boxed as refreshing emits capability events which check permissions again.
+    fn refresh_boxed(state: PlatformState, app_id: String) -> BoxFuture<'static, RippleResponse> {
+        async move { Self::refresh(&state, app_id).await }.boxed()
+    }
+
+    /// Fetches the permissions of the app from the distributor, emitting
+    /// `capabilities.onRevoked` for cached permissions which are no longer present.
+    pub async fn refresh(state: &PlatformState, app_id: String) -> RippleResponse {
+        let permitted_state = &state.cap_state.permitted_state;
+        if !permitted_state.start_refresh(&app_id) {
+            return Ok(());
+        }
+        let result = Self::store(state, &app_id).await;
+        permitted_state.end_refresh(&app_id);
+        for revoked in result? {
+            info!("Permission {} revoked for {}", revoked.cap.as_str(), app_id);
+            CapState::emit(state, CapEvent::OnRevoked, revoked.cap, Some(revoked.role)).await;
+        }
+        Ok(())
+    }
+
+    /// Fetches and caches the permissions of the app, returning the previously cached
+    /// permissions which are no longer present.
+    async fn store(
+        state: &PlatformState,
+        app_id: &str,
+    ) -> Result<Vec<FireboltPermission>, RippleError> {
+        let permissions = Self::fetch(state, app_id).await?;
+        let mut permitted_state = state.cap_state.permitted_state.clone();
+        let previous = permitted_state
+            .get_app_permissions(app_id)
+            .unwrap_or_default();
+        let mut map = HashMap::new();
+        map.insert(app_id.to_owned(), permissions.clone());
+        permitted_state.ingest(map);
+        info!("Permissions fetched for {}", app_id);
+        Ok(previous
+            .into_iter()
+            .filter(|p| !permissions.contains(p))
+            .collect())
+    }
+
+    /// Refreshes the permissions of every app fetched from the distributor, used when the
+    /// account session changes.
+    pub async fn refresh_all(state: PlatformState) {
+        for app_id in state.cap_state.permitted_state.get_cached_apps() {
+            if AppLibrary::get_manifest(&state.app_library_state, &app_id).is_some() {
+                continue;
+            }
+            if Self::refresh(&state, app_id.clone()).await.is_err() {
+                error!("Couldnt refresh permissions for app {}", app_id)
+            }
+        }
+    }
+
+    async fn fetch(
+        state: &PlatformState,
+        app_id: &str,
+    ) -> Result<Vec<FireboltPermission>, RippleError> {
         if let Some(session) = state.session_state.get_account_session() {
             if let Ok(extn_response) = state
                 .get_client()
                 .send_extn_request(PermissionRequest {
-                    app_id: app_id.clone(),
+                    app_id: app_id.to_owned(),
                     session,
                 })
                 .await
             {
                 if let Some(permission_response) = extn_response.payload.extract() {
-                    let mut map = HashMap::new();
-                    map.insert(app_id.clone(), permission_response);
-                    let mut permitted_state = state.cap_state.permitted_state.clone();
-                    permitted_state.ingest(map);
-                    info!("Permissions fetched for {}", app_id);
-                    return Ok(());
+                    return Ok(permission_response);
                 }
             }
         }
 
-        Err(ripple_sdk::utils::error::RippleError::InvalidOutput)
+        Err(RippleError::InvalidOutput)
     }
 
     pub fn get_permitted_info(
@@ -162,7 +358,7 @@ impl PermissionHandler {
         request: CapabilitySet,
     ) -> Result<(), DenyReasonWithCap> {
         if let Some(permitted) = state.cap_state.permitted_state.get_app_permissions(&app_id) {
-            return request.has_permissions(&permitted);
+            return request.has_permissions(&permitted, &state.cap_state.permitted_state.rules);
         } else {
             // check to retrieve it one more time
             if let Ok(_) = Self::fetch_and_store(state.clone(), app_id.into()).await {
@@ -170,7 +366,8 @@ impl PermissionHandler {
                 if let Some(permitted) =
                     state.cap_state.permitted_state.get_app_permissions(&app_id)
                 {
-                    return request.has_permissions(&permitted);
+                    return request
+                        .has_permissions(&permitted, &state.cap_state.permitted_state.rules);
                 }
             }
         }
@@ -0,0 +1,187 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::sync::{Arc, RwLock};
+
+use ripple_sdk::{
+    api::{
+        device::device_info_request::DeviceInfoRequest,
+        distributor::distributor_privacy::DataEventType,
+        firebolt::fb_metrics::{BehavioralMetricRequest, MetricsContext},
+        storage_property::StorageProperty,
+    },
+    extn::extn_client_message::ExtnResponse,
+    log::{debug, error},
+    utils::error::RippleError,
+};
+
+use crate::{
+    processor::storage::storage_manager::StorageManager, service::data_governance::DataGovernance,
+};
+
+use super::platform_state::PlatformState;
+
+/// Device and account context sent to the metrics pipeline ahead of the metrics.
+#[derive(Debug, Clone, Default)]
+pub struct MetricsState {
+    context: Arc<RwLock<Option<MetricsContext>>>,
+}
+
+impl MetricsState {
+    /// Resolves the data governance tags of the app and forwards the metric, returns false when
+    /// the policies drop it.
+    pub async fn send_metric(
+        state: &PlatformState,
+        mut metric: BehavioralMetricRequest,
+    ) -> Result<bool, RippleError> {
+        if let Some(app_id) = metric.get_context_mut().map(|c| c.app_id.clone()) {
+            let (data_tags, drop_data) = DataGovernance::resolve_tags(
+                state,
+                app_id.clone(),
+                DataEventType::BusinessIntelligence,
+            )
+            .await;
+            if drop_data {
+                debug!("metric of {} dropped by data governance", app_id);
+                return Ok(false);
+            }
+            if let Some(context) = metric.get_context_mut() {
+                context.data_tags = data_tags;
+            }
+        }
+        Self::update_context(state).await;
+        state.get_client().send_extn_request(metric).await?;
+        Ok(true)
+    }
+
+    /// Sends the device context again, called when the account session or the language
+    /// changed.
+    pub async fn refresh_context(state: &PlatformState) {
+        *state.metrics.context.write().unwrap() = None;
+        Self::update_context(state).await;
+    }
+
+    /// Sends the device context once the account session is available.
+    async fn update_context(state: &PlatformState) {
+        if state.metrics.context.read().unwrap().is_some() {
+            return;
+        }
+        let session = match state.session_state.get_account_session() {
+            Some(session) => session,
+            None => return,
+        };
+        let mut context = MetricsContext::new();
+        context.device_id = session.device_id;
+        context.account_id = session.account_id;
+        context.session_id = session.id;
+        context.platform = state
+            .get_device_manifest()
+            .get_device_platform()
+            .to_string();
+        if let Ok(response) = state
+            .get_client()
+            .send_extn_request(DeviceInfoRequest::Model)
+            .await
+        {
+            if let Some(ExtnResponse::String(model)) = response.payload.extract() {
+                context.device_model = model;
+            }
+        }
+        if let Ok(language) = StorageManager::get_string(state, StorageProperty::Language).await {
+            context.device_language = language;
+        }
+
+        let request = BehavioralMetricRequest::UpdateContext(context.clone());
+        match state.get_client().send_extn_request(request).await {
+            Ok(_) => {
+                *state.metrics.context.write().unwrap() = Some(context);
+            }
+            Err(e) => error!("Unable to update the metrics context {:?}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use ripple_sdk::{
+        api::{
+            distributor::distributor_privacy::{ExclusionPolicy, ExclusionPolicyData},
+            firebolt::fb_metrics::{BehavioralMetricContext, Ready},
+            manifest::{device_manifest::DeviceManifest, extn_manifest::ExtnManifest},
+        },
+        serde_json::{self, json},
+        tokio,
+    };
+
+    use super::*;
+    use crate::{
+        service::extn::ripple_client::RippleClient, state::bootstrap_state::ChannelsState,
+    };
+
+    fn platform_state() -> PlatformState {
+        let (_, extn_manifest) = ExtnManifest::load_from_content(
+            include_str!("../../../../examples/manifest/extn-manifest-example.json").to_owned(),
+        )
+        .unwrap();
+        let mut manifest: serde_json::Value = serde_json::from_str(include_str!(
+            "../../../../examples/manifest/device-manifest-example.json"
+        ))
+        .unwrap();
+        manifest["configuration"]["data_governance"] = json!({
+            "policies": [{
+                "data_type": "BusinessIntelligence",
+                "setting_tags": [{
+                    "setting": "AllowPersonalization",
+                    "tags": ["personalization"]
+                }]
+            }]
+        });
+        let (_, manifest) = DeviceManifest::load_from_content(manifest.to_string()).unwrap();
+        let client = RippleClient::new(ChannelsState::new());
+        PlatformState::new(extn_manifest, manifest, client, Vec::new())
+    }
+
+    #[tokio::test(crate = "ripple_sdk::tokio")]
+    async fn test_governance_drops_metric() {
+        let state = platform_state();
+        *state.data_governance.exclusions.write().unwrap() = Some(ExclusionPolicy {
+            personalization: Some(ExclusionPolicyData {
+                data_events: vec![DataEventType::BusinessIntelligence],
+                entity_reference: vec!["app".into()],
+                derivative_propagation: false,
+            }),
+            ..Default::default()
+        });
+        let metric = BehavioralMetricRequest::Ready(Ready {
+            context: BehavioralMetricContext {
+                app_id: "app".into(),
+                app_version: "1.0".into(),
+                partner_id: "partner".into(),
+                data_tags: HashSet::new(),
+            },
+            ttmu_ms: 10,
+        });
+
+        assert!(matches!(
+            MetricsState::send_metric(&state, metric).await,
+            Ok(false)
+        ));
+        assert!(state.metrics.context.read().unwrap().is_none());
+    }
+}
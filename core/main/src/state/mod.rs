@@ -17,6 +17,7 @@
 
 pub mod bootstrap_state;
 pub mod extn_state;
+pub mod metrics_state;
 pub mod openrpc_state;
 pub mod platform_state;
 pub mod session_state;
@@ -21,7 +21,7 @@ use ripple_sdk::api::{
     },
     manifest::exclusory::{Exclusory, ExclusoryImpl},
 };
-use ripple_sdk::{api::firebolt::fb_openrpc::CapabilityPolicy, serde_json};
+use ripple_sdk::{api::firebolt::fb_openrpc::CapabilityPolicy, log::warn, serde_json};
 use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
@@ -54,7 +54,15 @@ impl OpenRpcState {
     pub fn add_open_rpc(&self, version_manifest: FireboltVersionManifest) {
         let open_rpc: FireboltOpenRpc = version_manifest.clone().into();
         let cap_policies = version_manifest.capabilities;
-        let cap_map = open_rpc.clone().get_methods_caps();
+        let mut cap_map = open_rpc.clone().get_methods_caps();
+        // Extensions can't change how the core Firebolt methods are gated
+        cap_map.retain(|method, _| {
+            let is_core = self.is_core_method(method);
+            if is_core {
+                warn!("Ignoring extension capabilities for core method {}", method);
+            }
+            !is_core
+        });
 
         {
             self.cap_map.write().unwrap().extend(cap_map);
@@ -69,6 +77,13 @@ impl OpenRpcState {
         }
     }
 
+    fn is_core_method(&self, method: &str) -> bool {
+        self.open_rpc
+            .methods
+            .iter()
+            .any(|m| FireboltOpenRpcMethod::name_with_lowercase_module(&m.name) == method)
+    }
+
     pub fn is_excluded(&self, method: String, app_id: String) -> bool {
         if let Some(e) = &self.exclusory {
             if e.is_excluded(app_id, method.clone()) {
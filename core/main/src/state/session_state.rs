@@ -26,7 +26,7 @@ use ripple_sdk::{
         gateway::rpc_gateway_api::ApiMessage,
         session::{AccountSession, ProvisionRequest},
     },
-    tokio::sync::mpsc::Sender,
+    tokio::sync::{mpsc::Sender, Notify},
     utils::error::RippleError,
 };
 
@@ -40,6 +40,7 @@ pub struct SessionData {
 pub struct Session {
     sender: Option<Sender<ApiMessage>>,
     data: SessionData,
+    disconnect: Arc<Notify>,
 }
 
 impl Session {
@@ -51,9 +52,20 @@ impl Session {
         Session {
             sender,
             data: SessionData { app_id, transport },
+            disconnect: Arc::new(Notify::new()),
         }
     }
 
+    /// Asks the transport to close the session.
+    pub fn disconnect(&self) {
+        self.disconnect.notify_one();
+    }
+
+    /// Completes once the session has been asked to close.
+    pub async fn disconnected(&self) {
+        self.disconnect.notified().await
+    }
+
     pub fn get_sender(&self) -> Option<Sender<ApiMessage>> {
         self.sender.clone()
     }
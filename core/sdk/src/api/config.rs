@@ -25,6 +25,7 @@ use crate::{
     framework::ripple_contract::RippleContract,
 };
 
+use super::device::device_runtime::RuntimeRegistry;
 use super::manifest::{
     app_library::AppLibraryState,
     device_manifest::{LifecyclePolicy, RetentionPolicy},
@@ -103,6 +104,8 @@ pub struct LauncherConfig {
     pub retention_policy: RetentionPolicy,
     pub lifecycle_policy: LifecyclePolicy,
     pub app_library_state: AppLibraryState,
+    pub saved_dir: String,
+    pub runtime_registry: RuntimeRegistry,
 }
 
 impl ExtnPayloadProvider for LauncherConfig {
@@ -47,8 +47,12 @@ impl BrowserProps {
 pub struct BrowserLaunchParams {
     pub uri: String,
     pub browser_name: String,
-    #[serde(rename = "type")]
-    pub _type: String,
+    /// Id of a runtime from the [super::device_runtime::RuntimeRegistry], resolved by the
+    /// window manager into its own client type.
+    pub runtime: String,
+    /// Requests a client which stays loaded while the app is offline.
+    #[serde(default)]
+    pub resident: bool,
     pub visible: bool,
     pub suspend: bool,
     pub focused: bool,
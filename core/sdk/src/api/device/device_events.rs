@@ -18,7 +18,7 @@
 use std::str::FromStr;
 
 use crate::{
-    extn::extn_client_message::{ExtnPayload, ExtnPayloadProvider, ExtnRequest},
+    extn::extn_client_message::{ExtnEvent, ExtnPayload, ExtnPayloadProvider, ExtnRequest},
     framework::ripple_contract::RippleContract,
 };
 use serde::{Deserialize, Serialize};
@@ -35,6 +35,9 @@ pub const NETWORK_CHANGED_EVENT: &'static str = "device.onNetworkChanged";
 pub const AUDIO_CHANGED_EVENT: &'static str = "device.onAudioChanged";
 pub const VOICE_GUIDANCE_CHANGED: &'static str = "accessibility.onVoiceGuidanceSettingsChanged";
 pub const POWER_STATE_CHANGED: &'static str = "device.onPowerStateChanged";
+pub const HDMI_CONNECTION_CHANGED: &'static str = "device.onHdmiConnectionChanged";
+pub const REMOTE_PAIRING_CHANGED: &'static str = "device.onRemotePairingChanged";
+pub const TEXT_TO_SPEECH_STATE_CHANGED: &'static str = "device.onTextToSpeechStateChanged";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DeviceEvent {
@@ -46,6 +49,9 @@ pub enum DeviceEvent {
     NetworkChanged,
     AudioChanged,
     SystemPowerStateChanged,
+    HdmiConnectionChanged,
+    RemotePairingChanged,
+    TextToSpeechStateChanged,
 }
 
 impl FromStr for DeviceEvent {
@@ -61,12 +67,60 @@ impl FromStr for DeviceEvent {
             "device.onNetworkChanged" => Ok(Self::NetworkChanged),
             "device.onAudioChanged" => Ok(Self::AudioChanged),
             "device.onPowerStateChanged" => Ok(Self::SystemPowerStateChanged),
+            "device.onHdmiConnectionChanged" => Ok(Self::HdmiConnectionChanged),
+            "device.onRemotePairingChanged" => Ok(Self::RemotePairingChanged),
+            "device.onTextToSpeechStateChanged" => Ok(Self::TextToSpeechStateChanged),
             _ => Err(()),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Device states which capabilities can depend on to be available.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeviceCondition {
+    NetworkConnected,
+    HdmiConnected,
+    RemotePaired,
+    TextToSpeechActive,
+}
+
+impl DeviceCondition {
+    /// Device event which reports changes of the condition.
+    pub fn get_device_event(&self) -> DeviceEvent {
+        match self {
+            DeviceCondition::NetworkConnected => DeviceEvent::NetworkChanged,
+            DeviceCondition::HdmiConnected => DeviceEvent::HdmiConnectionChanged,
+            DeviceCondition::RemotePaired => DeviceEvent::RemotePairingChanged,
+            DeviceCondition::TextToSpeechActive => DeviceEvent::TextToSpeechStateChanged,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceConditionEvent {
+    pub condition: DeviceCondition,
+    pub active: bool,
+}
+
+impl ExtnPayloadProvider for DeviceConditionEvent {
+    fn get_extn_payload(&self) -> ExtnPayload {
+        ExtnPayload::Event(ExtnEvent::DeviceCondition(self.clone()))
+    }
+
+    fn get_from_payload(payload: ExtnPayload) -> Option<Self> {
+        match payload {
+            ExtnPayload::Event(ExtnEvent::DeviceCondition(d)) => Some(d),
+            _ => None,
+        }
+    }
+
+    fn contract() -> RippleContract {
+        RippleContract::DeviceConditionEvent
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum DeviceEventCallback {
     FireboltAppEvent,
     ExtnEvent,
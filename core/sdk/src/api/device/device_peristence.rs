@@ -74,10 +74,25 @@ pub struct SetStorageProperty {
     pub data: StorageData,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeleteStorageProperty {
+    pub namespace: String,
+    pub key: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum DevicePersistenceRequest {
     Get(GetStorageProperty),
     Set(SetStorageProperty),
+    Delete(DeleteStorageProperty),
+    /// Deletes every key of the namespace.
+    DeleteNamespace(String),
+    /// Lists the keys of the namespace, responds with [crate::extn::extn_client_message::ExtnResponse::List].
+    GetKeys(String),
+    /// Lists the namespaces, responds with [crate::extn::extn_client_message::ExtnResponse::List].
+    GetNamespaces,
+    /// Writes the pending changes of the device store to disk.
+    Flush,
 }
 
 impl ExtnPayloadProvider for DevicePersistenceRequest {
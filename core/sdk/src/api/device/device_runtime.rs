@@ -0,0 +1,115 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use serde::{Deserialize, Serialize};
+
+pub const WEB_RUNTIME: &str = "web";
+pub const LIGHTNING_RUNTIME: &str = "lightning";
+
+/// Features a runtime offers to the apps launched on it, independent of the window manager
+/// which hosts the runtime.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeCapabilities {
+    /// Apps can be kept loaded while offline and not visible.
+    #[serde(default)]
+    pub resident: bool,
+    /// Apps can be suspended instead of being destroyed.
+    #[serde(default)]
+    pub suspend: bool,
+    /// Browser local storage can be enabled for apps.
+    #[serde(default)]
+    pub local_storage: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RuntimeDescriptor {
+    /// Value matched against the `runtime` field of the app manifest.
+    pub id: String,
+    #[serde(default)]
+    pub capabilities: RuntimeCapabilities,
+}
+
+impl RuntimeDescriptor {
+    pub fn new(id: &str, capabilities: RuntimeCapabilities) -> RuntimeDescriptor {
+        RuntimeDescriptor {
+            id: id.into(),
+            capabilities,
+        }
+    }
+}
+
+/// Runtimes a device can host apps on. Window manager extensions resolve a runtime id
+/// into their own client types, so the launcher never needs to know about them.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RuntimeRegistry {
+    runtimes: Vec<RuntimeDescriptor>,
+}
+
+impl Default for RuntimeRegistry {
+    fn default() -> Self {
+        RuntimeRegistry {
+            runtimes: vec![
+                RuntimeDescriptor::new(
+                    WEB_RUNTIME,
+                    RuntimeCapabilities {
+                        local_storage: true,
+                        ..Default::default()
+                    },
+                ),
+                RuntimeDescriptor::new(
+                    LIGHTNING_RUNTIME,
+                    RuntimeCapabilities {
+                        resident: true,
+                        suspend: true,
+                        local_storage: true,
+                    },
+                ),
+            ],
+        }
+    }
+}
+
+impl RuntimeRegistry {
+    pub fn new(runtimes: Vec<RuntimeDescriptor>) -> RuntimeRegistry {
+        let mut registry = RuntimeRegistry {
+            runtimes: Vec::new(),
+        };
+        for runtime in runtimes {
+            registry.register(runtime);
+        }
+        registry
+    }
+
+    /// Adds a runtime, replacing any runtime previously registered with the same id.
+    pub fn register(&mut self, runtime: RuntimeDescriptor) {
+        self.runtimes.retain(|r| r.id != runtime.id);
+        self.runtimes.push(runtime);
+    }
+
+    pub fn get(&self, id: &str) -> Option<RuntimeDescriptor> {
+        self.runtimes.iter().find(|r| r.id.eq(id)).cloned()
+    }
+
+    pub fn supports(&self, id: &str) -> bool {
+        self.runtimes.iter().any(|r| r.id.eq(id))
+    }
+
+    pub fn get_runtimes(&self) -> Vec<RuntimeDescriptor> {
+        self.runtimes.clone()
+    }
+}
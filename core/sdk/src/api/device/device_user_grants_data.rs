@@ -113,8 +113,13 @@ pub struct GrantPolicy {
     pub scope: GrantScope,
     pub lifespan: GrantLifespan,
     pub overridable: bool,
+    /// Lifetime of `seconds` grants, also applied to grants stored before it changed.
     pub lifespan_ttl: Option<u64>,
     pub privacy_setting: Option<GrantPrivacySetting>,
+    /// Time after a user denial during which the user isn't prompted again and requests
+    /// are denied right away.
+    #[serde(default)]
+    pub denial_cooldown_secs: Option<u64>,
 }
 
 impl Default for GrantPolicy {
@@ -126,6 +131,7 @@ impl Default for GrantPolicy {
             overridable: true,
             lifespan_ttl: None,
             privacy_setting: None,
+            denial_cooldown_secs: None,
         }
     }
 }
@@ -258,6 +264,17 @@ impl GrantEntry {
         }
     }
 
+    /// Time since the epoch at which a `Seconds` grant expires.
+    pub fn expires_at(&self) -> Option<Duration> {
+        match self.lifespan {
+            Some(GrantLifespan::Seconds) => Some(
+                self.last_modified_time
+                    + Duration::from_secs(self.lifespan_ttl_in_secs.unwrap_or(0)),
+            ),
+            _ => None,
+        }
+    }
+
     pub fn has_expired(&self) -> bool {
         match self.lifespan {
             Some(GrantLifespan::Seconds) => match self.lifespan_ttl_in_secs {
@@ -278,6 +295,67 @@ impl GrantEntry {
     }
 }
 
+#[derive(Eq, Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum GrantAuditAction {
+    Granted,
+    Denied,
+    Cleared,
+    Expired,
+}
+
+/// A single grant decision or removal recorded in the grant audit log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantAuditRecord {
+    /// Milliseconds since the unix epoch.
+    pub timestamp: u64,
+    /// None for device scoped grants which were not decided on behalf of an app.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>,
+    pub capability: String,
+    pub role: CapabilityRole,
+    pub action: GrantAuditAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifespan: Option<GrantLifespan>,
+    /// Grant step or api which produced the record.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<String>,
+}
+
+impl GrantAuditRecord {
+    pub fn new(
+        app_id: Option<String>,
+        capability: String,
+        role: CapabilityRole,
+        action: GrantAuditAction,
+    ) -> GrantAuditRecord {
+        GrantAuditRecord {
+            timestamp: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            app_id,
+            capability,
+            role,
+            action,
+            lifespan: None,
+            step: None,
+        }
+    }
+
+    pub fn from_entry(
+        app_id: Option<String>,
+        entry: &GrantEntry,
+        action: GrantAuditAction,
+    ) -> GrantAuditRecord {
+        let mut record =
+            GrantAuditRecord::new(app_id, entry.capability.clone(), entry.role.clone(), action);
+        record.lifespan = entry.lifespan.clone();
+        record
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum GrantActiveState {
     ActiveGrant(Result<(), DenyReason>),
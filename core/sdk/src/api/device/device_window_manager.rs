@@ -32,6 +32,8 @@ pub enum WindowManagerRequest {
     MoveToBack(String),
     Focus(String),
     Dimensions(String, Dimensions),
+    /// Lists the names of the clients currently loaded in the window manager.
+    GetClients,
 }
 
 impl WindowManagerRequest {
@@ -42,6 +44,7 @@ impl WindowManagerRequest {
             WindowManagerRequest::MoveToBack(wn) => wn,
             WindowManagerRequest::Focus(wn) => wn,
             WindowManagerRequest::Dimensions(wn, _) => wn,
+            WindowManagerRequest::GetClients => return String::default(),
         }
         .clone()
     }
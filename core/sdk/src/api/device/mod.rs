@@ -25,6 +25,7 @@ pub mod device_info_request;
 pub mod device_operator;
 pub mod device_peristence;
 pub mod device_request;
+pub mod device_runtime;
 pub mod device_user_grants_data;
 pub mod device_wifi;
 pub mod device_window_manager;
@@ -15,7 +15,10 @@
 // SPDX-License-Identifier: Apache-2.0
 //
 
-use std::hash::{Hash, Hasher};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -124,6 +127,64 @@ pub struct FireboltPermission {
     pub role: CapabilityRole,
 }
 
+/// Device configured rules for matching the permissions granted to an app against the
+/// permissions a request needs.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionRules {
+    /// Roles implied by a granted role, e.g. `{"manage": ["use"]}`, implications are transitive.
+    #[serde(default)]
+    pub role_implications: HashMap<CapabilityRole, Vec<CapabilityRole>>,
+    /// Allows granted capabilities ending with `:*` to cover every capability in the namespace.
+    #[serde(default)]
+    pub wildcards: bool,
+}
+
+impl PermissionRules {
+    pub fn implies_role(&self, granted: &CapabilityRole, requested: &CapabilityRole) -> bool {
+        let mut visited = HashSet::new();
+        let mut pending = vec![granted.clone()];
+        while let Some(role) = pending.pop() {
+            if role == *requested {
+                return true;
+            }
+            if visited.insert(role.clone()) {
+                if let Some(implied) = self.role_implications.get(&role) {
+                    pending.extend(implied.iter().cloned());
+                }
+            }
+        }
+        false
+    }
+
+    pub fn covers_cap(&self, granted: &FireboltCap, requested: &FireboltCap) -> bool {
+        let granted = granted.as_str();
+        let requested = requested.as_str();
+        if granted == requested {
+            return true;
+        }
+        match granted.strip_suffix('*') {
+            Some(namespace) if self.wildcards && namespace.ends_with(':') => {
+                requested.starts_with(namespace)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn permits(&self, granted: &FireboltPermission, requested: &FireboltPermission) -> bool {
+        self.covers_cap(&granted.cap, &requested.cap)
+            && self.implies_role(&granted.role, &requested.role)
+    }
+
+    pub fn is_permitted(
+        &self,
+        granted: &[FireboltPermission],
+        requested: &FireboltPermission,
+    ) -> bool {
+        granted.iter().any(|g| self.permits(g, requested))
+    }
+}
+
 impl Serialize for FireboltPermission {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -310,7 +371,7 @@ impl RpcError for DenyReason {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DenyReasonWithCap {
     pub reason: DenyReason,
     pub caps: Vec<FireboltCap>,
@@ -363,3 +424,47 @@ impl CapEvent {
         serde_json::to_string(&self).unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::api::firebolt::fb_capabilities::{
+        CapabilityRole, FireboltCap, FireboltPermission, PermissionRules,
+    };
+
+    fn permission(cap: &str, role: CapabilityRole) -> FireboltPermission {
+        FireboltPermission {
+            cap: FireboltCap::Full(cap.into()),
+            role,
+        }
+    }
+
+    #[test]
+    fn test_permission_rules() {
+        let rules: PermissionRules = serde_json::from_str(
+            r#"{"roleImplications": {"provide": ["manage"], "manage": ["use"]}, "wildcards": true}"#,
+        )
+        .unwrap();
+        let granted = vec![permission(
+            "xrn:firebolt:capability:device:*",
+            CapabilityRole::Provide,
+        )];
+        assert!(rules.is_permitted(
+            &granted,
+            &permission("xrn:firebolt:capability:device:model", CapabilityRole::Use)
+        ));
+        assert!(!rules.is_permitted(
+            &granted,
+            &permission("xrn:firebolt:capability:account:uid", CapabilityRole::Use)
+        ));
+
+        let exact = PermissionRules::default();
+        assert!(!exact.is_permitted(
+            &granted,
+            &permission(
+                "xrn:firebolt:capability:device:model",
+                CapabilityRole::Provide
+            )
+        ));
+        assert!(!exact.implies_role(&CapabilityRole::Manage, &CapabilityRole::Use));
+    }
+}
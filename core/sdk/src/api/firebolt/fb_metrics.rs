@@ -15,7 +15,7 @@
 // SPDX-License-Identifier: Apache-2.0
 //
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use async_trait::async_trait;
 use log::info;
@@ -37,6 +37,9 @@ pub struct BehavioralMetricContext {
     pub app_id: String,
     pub app_version: String,
     pub partner_id: String,
+    /// Data governance tags resolved for the app when the metric was emitted.
+    #[serde(default)]
+    pub data_tags: HashSet<String>,
 }
 
 impl From<CallContext> for BehavioralMetricContext {
@@ -45,6 +48,7 @@ impl From<CallContext> for BehavioralMetricContext {
             app_id: call_context.app_id,
             app_version: call_context.session_id.clone(),
             partner_id: call_context.session_id,
+            data_tags: HashSet::new(),
         }
     }
 }
@@ -281,6 +285,40 @@ pub enum BehavioralMetricRequest {
     TelemetrySignOut(fb_telemetry::SignOut),
     TelemetryInternalInitialize(fb_telemetry::InternalInitialize),
     Raw(RawBehaviorMetricRequest),
+    /// Device and account context to enrich the metrics which follow.
+    UpdateContext(MetricsContext),
+}
+
+impl BehavioralMetricRequest {
+    /// Returns the app context of the metric, telemetry and context updates have none.
+    pub fn get_context_mut(&mut self) -> Option<&mut BehavioralMetricContext> {
+        match self {
+            BehavioralMetricRequest::Ready(m) => Some(&mut m.context),
+            BehavioralMetricRequest::SignIn(m) => Some(&mut m.context),
+            BehavioralMetricRequest::SignOut(m) => Some(&mut m.context),
+            BehavioralMetricRequest::StartContent(m) => Some(&mut m.context),
+            BehavioralMetricRequest::StopContent(m) => Some(&mut m.context),
+            BehavioralMetricRequest::Page(m) => Some(&mut m.context),
+            BehavioralMetricRequest::Action(m) => Some(&mut m.context),
+            BehavioralMetricRequest::Error(m) => Some(&mut m.context),
+            BehavioralMetricRequest::MediaLoadStart(m) => Some(&mut m.context),
+            BehavioralMetricRequest::MediaPlay(m) => Some(&mut m.context),
+            BehavioralMetricRequest::MediaPlaying(m) => Some(&mut m.context),
+            BehavioralMetricRequest::MediaPause(m) => Some(&mut m.context),
+            BehavioralMetricRequest::MediaWaiting(m) => Some(&mut m.context),
+            BehavioralMetricRequest::MediaProgress(m) => Some(&mut m.context),
+            BehavioralMetricRequest::MediaSeeking(m) => Some(&mut m.context),
+            BehavioralMetricRequest::MediaSeeked(m) => Some(&mut m.context),
+            BehavioralMetricRequest::MediaRateChanged(m) => Some(&mut m.context),
+            BehavioralMetricRequest::MediaRenditionChanged(m) => Some(&mut m.context),
+            BehavioralMetricRequest::MediaEnded(m) => Some(&mut m.context),
+            BehavioralMetricRequest::Raw(m) => Some(&mut m.context),
+            BehavioralMetricRequest::TelemetrySignIn(_)
+            | BehavioralMetricRequest::TelemetrySignOut(_)
+            | BehavioralMetricRequest::TelemetryInternalInitialize(_)
+            | BehavioralMetricRequest::UpdateContext(_) => None,
+        }
+    }
 }
 
 impl ExtnPayloadProvider for BehavioralMetricRequest {
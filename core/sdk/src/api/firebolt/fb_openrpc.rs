@@ -20,9 +20,11 @@ use std::collections::{HashMap, HashSet};
 use log::{debug, warn};
 use serde::{Deserialize, Serialize};
 
+use crate::utils::error::RippleError;
+
 use super::fb_capabilities::{
     CapRequestRpcRequest, CapabilityRole, DenyReason, DenyReasonWithCap, FireboltCap,
-    FireboltPermission,
+    FireboltPermission, PermissionRules,
 };
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -79,6 +81,32 @@ impl FireboltVersionManifest {
         }
         self.apis.get(&max_api_version).cloned()
     }
+
+    /// Parses the OpenRPC fragment an extension ships for its methods, which is either a
+    /// version manifest or a plain OpenRPC document carrying the capability tags.
+    pub fn from_fragment(contents: &str) -> Result<FireboltVersionManifest, RippleError> {
+        if let Ok(version_manifest) = serde_json::from_str::<FireboltVersionManifest>(contents) {
+            if version_manifest.get_latest_rpc().is_some() {
+                return Ok(version_manifest);
+            }
+        }
+        match serde_json::from_str::<OpenRPCParser>(contents) {
+            Ok(parser) if parser.info.version.split('.').count() == 3 => {
+                Ok(FireboltVersionManifest {
+                    capabilities: HashMap::new(),
+                    apis: HashMap::from([(parser.info.version.clone(), parser)]),
+                })
+            }
+            Ok(parser) => {
+                warn!("Invalid open rpc fragment version {}", parser.info.version);
+                Err(RippleError::ParseError)
+            }
+            Err(e) => {
+                warn!("Could not parse open rpc fragment {:?}", e);
+                Err(RippleError::ParseError)
+            }
+        }
+    }
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -498,6 +526,7 @@ impl CapabilitySet {
     pub fn has_permissions(
         &self,
         permissions: &Vec<FireboltPermission>,
+        rules: &PermissionRules,
     ) -> Result<(), DenyReasonWithCap> {
         let mut result = true;
         let mut caps_not_permitted: Vec<FireboltCap> = Default::default();
@@ -507,7 +536,7 @@ impl CapabilitySet {
                     cap: cap.to_owned(),
                     role: CapabilityRole::Use,
                 };
-                if !permissions.contains(&perm) {
+                if !rules.is_permitted(permissions, &perm) {
                     result = false;
                     debug!("use caps not present: {:?}", perm);
                     caps_not_permitted.push(cap.to_owned());
@@ -520,7 +549,7 @@ impl CapabilitySet {
                 cap: provided.to_owned(),
                 role: CapabilityRole::Provide,
             };
-            result = permissions.contains(&perm);
+            result = rules.is_permitted(permissions, &perm);
             if !result {
                 debug!("provide caps not present: {:?}", perm);
                 caps_not_permitted.push(provided.to_owned());
@@ -533,7 +562,7 @@ impl CapabilitySet {
                     cap: cap.to_owned(),
                     role: CapabilityRole::Use,
                 };
-                if !permissions.contains(&perm) {
+                if !rules.is_permitted(permissions, &perm) {
                     result = false;
                     debug!("manage caps not present: {:?}", perm);
                     caps_not_permitted.push(cap.to_owned());
@@ -59,6 +59,14 @@ pub struct SecureStorageRemoveRequest {
     pub distributor_session: AccountSession,
 }
 
+/// Removes every key of an app in a scope, `None` applies to all apps or to both scopes.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SecureStorageClearRequest {
+    pub app_id: Option<String>,
+    pub scope: Option<StorageScope>,
+    pub distributor_session: AccountSession,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SecureStorageGetResponse {
     pub value: Option<String>,
@@ -94,11 +102,47 @@ pub struct RemoveRequest {
     pub scope: StorageScope,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClearRequest {
+    pub scope: StorageScope,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SetAppRequest {
+    pub app_id: String,
+    pub scope: StorageScope,
+    pub key: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<StorageOptions>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoveAppRequest {
+    pub app_id: String,
+    pub scope: StorageScope,
+    pub key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearAppRequest {
+    pub app_id: String,
+    pub scope: StorageScope,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum SecureStorageRequest {
     Get(SecureStorageGetRequest),
     Set(SecureStorageSetRequest),
     Remove(SecureStorageRemoveRequest),
+    Clear(SecureStorageClearRequest),
+    /// Set on behalf of another app by an operator app.
+    SetForApp(SecureStorageSetRequest),
+    /// Remove on behalf of another app by an operator app.
+    RemoveForApp(SecureStorageRemoveRequest),
 }
 
 impl ExtnPayloadProvider for SecureStorageRequest {
@@ -18,6 +18,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::fb_capabilities::CapabilityRole;
+use crate::api::device::device_user_grants_data::GrantAuditAction;
 
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -64,3 +65,29 @@ pub struct GrantRequest {
     pub capability: String,
     pub options: Option<GrantModificationOptions>,
 }
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantHistoryRequest {
+    pub app_id: Option<String>,
+    pub capability: Option<String>,
+    /// RFC 3339 date time
+    pub from: Option<String>,
+    /// RFC 3339 date time
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantHistoryInfo {
+    pub time: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<String>, //None in case of device
+    pub capability: String,
+    pub role: String,
+    pub action: GrantAuditAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lifespan: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<String>,
+}
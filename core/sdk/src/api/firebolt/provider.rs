@@ -17,8 +17,12 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::api::device::entertainment_data::{
-    EntityInfoParameters, EntityInfoResult, PurchasedContentParameters, PurchasedContentResult,
+use crate::{
+    api::device::entertainment_data::{
+        EntityInfoParameters, EntityInfoResult, PurchasedContentParameters, PurchasedContentResult,
+    },
+    extn::extn_client_message::{ExtnPayload, ExtnPayloadProvider, ExtnRequest},
+    framework::ripple_contract::RippleContract,
 };
 
 use super::{
@@ -105,6 +109,38 @@ pub struct ProviderResponse {
     pub result: ProviderResponsePayload,
 }
 
+/// Provider request handed to the fallback provider extension when no app has registered
+/// as provider. The extension answers pin challenges with [ExtnResponse::PinChallenge],
+/// keyboard sessions with [ExtnResponse::Keyboard] and acknowledge challenges with
+/// [ExtnResponse::Boolean].
+///
+/// [ExtnResponse::PinChallenge]: crate::extn::extn_client_message::ExtnResponse::PinChallenge
+/// [ExtnResponse::Keyboard]: crate::extn::extn_client_message::ExtnResponse::Keyboard
+/// [ExtnResponse::Boolean]: crate::extn::extn_client_message::ExtnResponse::Boolean
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FallbackProviderRequest {
+    pub capability: String,
+    pub method: String,
+    pub parameters: ProviderRequestPayload,
+}
+
+impl ExtnPayloadProvider for FallbackProviderRequest {
+    fn get_extn_payload(&self) -> ExtnPayload {
+        ExtnPayload::Request(ExtnRequest::FallbackProvider(self.clone()))
+    }
+
+    fn get_from_payload(payload: ExtnPayload) -> Option<Self> {
+        if let ExtnPayload::Request(ExtnRequest::FallbackProvider(r)) = payload {
+            return Some(r);
+        }
+        None
+    }
+
+    fn contract() -> RippleContract {
+        RippleContract::FallbackProvider
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ExternalProviderRequest<T> {
@@ -17,7 +17,10 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::api::device::device_browser::BrowserProps;
+use crate::api::{
+    device::device_browser::BrowserProps,
+    firebolt::fb_capabilities::{CapabilityRole, FireboltCap, FireboltPermission},
+};
 
 const X_DEFAULT: u32 = 0;
 const Y_DEFAULT: u32 = 0;
@@ -59,6 +62,49 @@ impl AppProperties {
     }
 }
 
+/// Number of concurrent instances an app may have loaded.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AppInstances {
+    /// A launch of a loaded app reuses the existing instance.
+    #[default]
+    Single,
+    /// A launch of a loaded app starts an additional instance.
+    Multi,
+}
+
+/// Behavior when a single instance app is launched while already loaded.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RelaunchPolicy {
+    /// Bring the app to the foreground and deliver the new intent through `discovery.onNavigateTo`.
+    #[default]
+    Navigate,
+    /// Unload the running instance and launch it again with the new intent.
+    Restart,
+}
+
+/// Window layer an app container is placed in. Layers are ordered bottom to top, a
+/// container is always stacked above every container of a lower layer.
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerLayer {
+    #[default]
+    Main,
+    Pip,
+    Overlay,
+    System,
+}
+
+impl ContainerLayer {
+    /// Picture-in-picture containers are displayed but never receive focus.
+    pub fn takes_focus(&self) -> bool {
+        !matches!(self, ContainerLayer::Pip)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppManifest {
     pub app_key: String,
@@ -76,12 +122,43 @@ pub struct AppManifest {
     pub h: u32,
     pub capabilities: AppCapabilities,
     pub properties: Option<AppProperties>,
+    #[serde(default)]
+    pub instances: AppInstances,
+    #[serde(default)]
+    pub relaunch: RelaunchPolicy,
+    #[serde(default)]
+    pub layer: ContainerLayer,
 }
 
 impl AppManifest {
     pub fn requires_capability(&self, cap: &'static str) -> bool {
         self.capabilities.used.required.contains(&String::from(cap))
     }
+
+    /// Permissions declared by the manifest, capabilities are permitted for the role they
+    /// are listed under regardless of whether they are required or optional.
+    pub fn get_permissions(&self) -> Vec<FireboltPermission> {
+        let mut permissions = Vec::new();
+        for (capability, role) in [
+            (&self.capabilities.used, CapabilityRole::Use),
+            (&self.capabilities.managed, CapabilityRole::Manage),
+            (&self.capabilities.provided, CapabilityRole::Provide),
+        ] {
+            let caps = capability
+                .required
+                .iter()
+                .chain(capability.optional.iter())
+                .cloned()
+                .collect();
+            for cap in FireboltCap::from_vec_string(caps) {
+                permissions.push(FireboltPermission {
+                    cap,
+                    role: role.clone(),
+                });
+            }
+        }
+        permissions
+    }
 }
 
 fn x_default() -> u32 {
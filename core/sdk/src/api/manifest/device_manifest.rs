@@ -26,11 +26,17 @@ use std::{
 
 use crate::{
     api::{
-        device::{device_user_grants_data::GrantPolicies, DevicePlatformType},
+        device::{
+            device_events::DeviceCondition,
+            device_runtime::{RuntimeDescriptor, RuntimeRegistry},
+            device_user_grants_data::GrantPolicies,
+            DevicePlatformType,
+        },
         distributor::distributor_privacy::DataEventType,
-        firebolt::fb_capabilities::FireboltCap,
+        firebolt::fb_capabilities::{FireboltCap, PermissionRules},
         storage_property::StorageProperty,
     },
+    framework::store_cipher::StoreEncryptionConfig,
     utils::error::RippleError,
 };
 
@@ -62,6 +68,86 @@ pub struct RippleConfiguration {
     #[serde(default = "data_governance_default")]
     pub data_governance: DataGovernanceConfig,
     pub partner_exclusion_refresh_timeout: Option<u32>,
+    /// Encrypts the grant and permission stores under the saved dir when present.
+    #[serde(default)]
+    pub store_encryption: Option<StoreEncryptionConfig>,
+    #[serde(default)]
+    pub sticky_events: StickyEventsConfig,
+    #[serde(default)]
+    pub event_delivery: EventDeliveryConfig,
+}
+
+/// Handling of websocket sessions which don't read their events fast enough.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EventDeliveryConfig {
+    /// Events which can wait to be written to a session before the policy applies, unused
+    /// with [SlowConsumerPolicy::Backpressure].
+    #[serde(default = "event_delivery_max_pending_default")]
+    pub max_pending: usize,
+    #[serde(default = "slow_consumer_policy_default")]
+    pub slow_consumer_policy: SlowConsumerPolicy,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SlowConsumerPolicy {
+    /// Holds up the sender of the event until the session has room for it.
+    Backpressure,
+    /// Drops the oldest pending event.
+    DropOldest,
+    /// Replaces the pending event of the same event name, the oldest one is dropped if there
+    /// is none.
+    Coalesce,
+    /// Closes the session.
+    Disconnect,
+}
+
+impl Default for EventDeliveryConfig {
+    fn default() -> Self {
+        EventDeliveryConfig {
+            max_pending: EVENT_DELIVERY_MAX_PENDING_DEFAULT,
+            slow_consumer_policy: slow_consumer_policy_default(),
+        }
+    }
+}
+
+const EVENT_DELIVERY_MAX_PENDING_DEFAULT: usize = 64;
+
+fn event_delivery_max_pending_default() -> usize {
+    EVENT_DELIVERY_MAX_PENDING_DEFAULT
+}
+
+fn slow_consumer_policy_default() -> SlowConsumerPolicy {
+    SlowConsumerPolicy::Backpressure
+}
+
+/// Events whose last values are replayed to apps which start listening after they were
+/// emitted.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StickyEventsConfig {
+    /// Number of values retained per app and event context, keyed by event name.
+    #[serde(default)]
+    pub events: HashMap<String, usize>,
+    /// Memory the retained values may use, the oldest values are dropped first.
+    #[serde(default = "sticky_events_max_size_kb_default")]
+    pub max_size_kb: u64,
+}
+
+impl Default for StickyEventsConfig {
+    fn default() -> Self {
+        StickyEventsConfig {
+            events: HashMap::new(),
+            max_size_kb: STICKY_EVENTS_MAX_SIZE_KB_DEFAULT,
+        }
+    }
+}
+
+const STICKY_EVENTS_MAX_SIZE_KB_DEFAULT: u64 = 64;
+
+fn sticky_events_max_size_kb_default() -> u64 {
+    STICKY_EVENTS_MAX_SIZE_KB_DEFAULT
 }
 
 fn data_governance_default() -> DataGovernanceConfig {
@@ -73,6 +159,88 @@ fn data_governance_default() -> DataGovernanceConfig {
 pub struct CapabilityConfiguration {
     pub supported: Vec<String>,
     pub grant_policies: Option<HashMap<String, GrantPolicies>>,
+    /// Refuse to launch apps which require capabilities the device doesn't support, only
+    /// a warning is logged when disabled.
+    #[serde(default)]
+    pub enforce_required: bool,
+    /// Time after which permissions fetched from the distributor are refreshed.
+    #[serde(default = "permissions_ttl_secs_default")]
+    pub permissions_ttl_secs: u64,
+    /// Size after which the grant audit log is rotated, the previous log is kept.
+    #[serde(default = "grant_audit_max_size_kb_default")]
+    pub grant_audit_max_size_kb: u64,
+    #[serde(default)]
+    pub permission_rules: PermissionRules,
+    /// Capabilities which are only available while a device condition holds, e.g.
+    /// `{"networkConnected": ["xrn:firebolt:capability:network:status"]}`.
+    #[serde(default)]
+    pub availability_conditions: HashMap<DeviceCondition, Vec<String>>,
+    #[serde(default)]
+    pub provider_broker: ProviderBrokerConfig,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderBrokerConfig {
+    /// Requests which can wait for a provider to register, further requests are rejected.
+    #[serde(default = "provider_queue_size_default")]
+    pub queue_size: usize,
+    /// Time in milliseconds a provider has to respond, keyed by capability. Requests for
+    /// capabilities without a timeout wait until the provider responds.
+    #[serde(default)]
+    pub response_timeouts_ms: HashMap<String, u64>,
+    /// Criteria deciding which of the providers registered for a capability gets a
+    /// request, later criteria only break ties of the earlier ones.
+    #[serde(default = "provider_selection_default")]
+    pub selection: Vec<ProviderSelection>,
+    /// Preferred provider apps in order, keyed by capability.
+    #[serde(default)]
+    pub priorities: HashMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderSelection {
+    Priority,
+    Focused,
+    MostRecent,
+}
+
+fn provider_selection_default() -> Vec<ProviderSelection> {
+    vec![
+        ProviderSelection::Priority,
+        ProviderSelection::Focused,
+        ProviderSelection::MostRecent,
+    ]
+}
+
+impl Default for ProviderBrokerConfig {
+    fn default() -> Self {
+        ProviderBrokerConfig {
+            queue_size: PROVIDER_QUEUE_SIZE_DEFAULT,
+            response_timeouts_ms: HashMap::new(),
+            selection: provider_selection_default(),
+            priorities: HashMap::new(),
+        }
+    }
+}
+
+const PROVIDER_QUEUE_SIZE_DEFAULT: usize = 3;
+
+fn provider_queue_size_default() -> usize {
+    PROVIDER_QUEUE_SIZE_DEFAULT
+}
+
+const PERMISSIONS_TTL_SECS_DEFAULT: u64 = 24 * 60 * 60;
+
+fn permissions_ttl_secs_default() -> u64 {
+    PERMISSIONS_TTL_SECS_DEFAULT
+}
+
+const GRANT_AUDIT_MAX_SIZE_KB_DEFAULT: u64 = 256;
+
+fn grant_audit_max_size_kb_default() -> u64 {
+    GRANT_AUDIT_MAX_SIZE_KB_DEFAULT
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -80,9 +248,14 @@ pub struct CapabilityConfiguration {
 pub struct LifecycleConfiguration {
     pub app_ready_timeout_ms: u64,
     pub app_finished_timeout_ms: u64,
+    #[serde(default = "launch_dedup_window_ms_default")]
+    pub launch_dedup_window_ms: u64,
     pub max_loaded_apps: u64,
     pub min_available_memory_kb: u64,
     pub prioritized: Vec<String>,
+    /// Runtimes the window manager can host, defaults to web and lightning when empty.
+    #[serde(default)]
+    pub runtimes: Vec<RuntimeDescriptor>,
 }
 
 /// Device manifest contains all the specifications required for coniguration of a Ripple application.
@@ -162,13 +335,22 @@ pub const DEFAULT_RETENTION_POLICY: RetentionPolicy = RetentionPolicy {
 pub struct LifecyclePolicy {
     pub app_ready_timeout_ms: u64,
     pub app_finished_timeout_ms: u64,
+    /// Identical launch requests for the same app received within this window are ignored.
+    pub launch_dedup_window_ms: u64,
 }
 
+const LAUNCH_DEDUP_WINDOW_MS_DEFAULT: u64 = 1000;
+
 pub const DEFAULT_LIFECYCLE_POLICY: LifecyclePolicy = LifecyclePolicy {
     app_ready_timeout_ms: 30000,
     app_finished_timeout_ms: 2000,
+    launch_dedup_window_ms: LAUNCH_DEDUP_WINDOW_MS_DEFAULT,
 };
 
+fn launch_dedup_window_ms_default() -> u64 {
+    LAUNCH_DEDUP_WINDOW_MS_DEFAULT
+}
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct IdSalt {
     pub algorithm: Option<String>,
@@ -432,6 +614,7 @@ impl DeviceManifest {
         LifecyclePolicy {
             app_ready_timeout_ms: self.lifecycle.app_ready_timeout_ms,
             app_finished_timeout_ms: self.lifecycle.app_finished_timeout_ms,
+            launch_dedup_window_ms: self.lifecycle.launch_dedup_window_ms,
         }
     }
 
@@ -443,6 +626,13 @@ impl DeviceManifest {
         }
     }
 
+    pub fn get_runtime_registry(&self) -> RuntimeRegistry {
+        match self.lifecycle.runtimes.is_empty() {
+            true => RuntimeRegistry::default(),
+            false => RuntimeRegistry::new(self.lifecycle.runtimes.clone()),
+        }
+    }
+
     pub fn get_supported_caps(&self) -> Vec<FireboltCap> {
         FireboltCap::from_vec_string(self.clone().capabilities.supported)
     }
@@ -415,8 +415,22 @@ pub struct PropertyData {
     pub event_names: Option<&'static [&'static str]>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum StorageProperty {
+/// Declares [StorageProperty] along with the list of all its variants.
+macro_rules! storage_properties {
+    ($($property:ident),* $(,)?) => {
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        pub enum StorageProperty {
+            $($property),*
+        }
+
+        impl StorageProperty {
+            /// Every storage property, in declaration order.
+            pub const ALL: &'static [StorageProperty] = &[$(StorageProperty::$property),*];
+        }
+    };
+}
+
+storage_properties! {
     ClosedCaptionsEnabled,
     ClosedCaptionsFontFamily,
     ClosedCaptionsFontSize,
@@ -556,6 +570,10 @@ impl StorageProperty {
 pub enum StorageManagerRequest {
     GetBool(StorageProperty, bool),
     GetString(StorageProperty),
+    /// Deletes the stored value so the property reads its default again.
+    Reset(StorageProperty),
+    /// Deletes every stored value of the namespace.
+    ResetNamespace(String),
 }
 
 impl ExtnPayloadProvider for StorageManagerRequest {
@@ -365,6 +365,14 @@ impl ExtnClient {
         }
     }
 
+    /// Checks if an extension has been added which fulfills the given contract
+    pub fn has_contract(&self, contract: RippleContract) -> bool {
+        self.contract_map
+            .read()
+            .unwrap()
+            .contains_key(&contract.as_clear_string())
+    }
+
     fn get_extn_sender_with_contract(
         &self,
         contract: RippleContract,
@@ -29,7 +29,7 @@ use crate::{
         caps::CapsRequest,
         config::{Config, ConfigResponse},
         device::{
-            device_events::DeviceEventRequest,
+            device_events::{DeviceConditionEvent, DeviceEventRequest},
             device_peristence::StorageData,
             device_request::{DeviceRequest, NetworkResponse, SystemPowerState},
         },
@@ -47,6 +47,7 @@ use crate::{
             fb_metrics::BehavioralMetricRequest,
             fb_pin::{PinChallengeRequestWithContext, PinChallengeResponse},
             fb_secure_storage::{SecureStorageRequest, SecureStorageResponse},
+            provider::FallbackProviderRequest,
         },
         gateway::rpc_gateway_api::RpcRequest,
         manifest::device_manifest::AppLibraryEntry,
@@ -249,6 +250,7 @@ pub enum ExtnRequest {
     LifecycleManagement(LifecycleManagementRequest),
     PinChallenge(PinChallengeRequestWithContext),
     Keyboard(KeyboardSessionRequest),
+    FallbackProvider(FallbackProviderRequest),
     Permission(PermissionRequest),
     Distributor(DistributorRequest),
     AccountSession(AccountSessionRequest),
@@ -322,6 +324,7 @@ pub enum ExtnEvent {
     Status(ExtnStatus),
     AppEvent(AppEventRequest),
     PowerState(SystemPowerState),
+    DeviceCondition(DeviceConditionEvent),
 }
 
 impl ExtnPayloadProvider for ExtnEvent {
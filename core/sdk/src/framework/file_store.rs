@@ -17,18 +17,35 @@
 
 use log::{debug, info, warn};
 use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
 use std::{
-    fs::{self, OpenOptions},
+    cell::Cell,
+    fs::{self, File, OpenOptions},
     io::Write,
     path::Path,
 };
 
+use super::store_cipher::StoreCipher;
 use crate::utils::error::RippleError;
 
+const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+const SCHEMA_VALUE_KEY: &str = "value";
+
+/// Migrates stored json from one schema version to the next.
+pub type FileStoreMigration = fn(Value) -> Result<Value, RippleError>;
+
+/// Json value persisted to a file.
+///
+/// Writes go to a temporary file which is synced and renamed over the store, the previous
+/// file is kept as a backup and used when the store can't be parsed on load. Stores with a
+/// [StoreCipher] are encrypted at rest.
 #[derive(Debug, Clone)]
 pub struct FileStore<S> {
     pub value: S,
     path: String,
+    version: Option<u32>,
+    cipher: Option<StoreCipher>,
+    read_only: bool,
 }
 
 impl<S> FileStore<S>
@@ -39,28 +56,179 @@ where
         FileStore {
             value: value.clone(),
             path: Path::new(&path).to_str().unwrap().into(),
+            version: None,
+            cipher: None,
+            read_only: false,
         }
     }
 
+    /// Encrypts the store on the next sync, None keeps the store in plain json.
+    pub fn with_cipher(mut self, cipher: Option<StoreCipher>) -> FileStore<S> {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Keeps the value in memory only, syncs leave the file untouched.
+    pub fn read_only(mut self) -> FileStore<S> {
+        self.read_only = true;
+        self
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn exists(&self) -> bool {
+        Path::new(&self.path).exists()
+    }
+
+    /// Loads the store, creating it with the default value when there is no file yet.
+    ///
+    /// An encrypted store is never replaced by an empty one. When the device key is
+    /// unavailable or the existing file can't be decrypted, the store is kept in memory only
+    /// and the file is left untouched.
+    pub fn open(
+        path: String,
+        default: S,
+        cipher: Result<Option<StoreCipher>, RippleError>,
+    ) -> FileStore<S> {
+        Self::open_with(path, default, None, cipher, Self::load_with_cipher)
+    }
+
+    /// Versioned variant of [FileStore::open], see [FileStore::load_versioned].
+    pub fn open_versioned(
+        path: String,
+        default: S,
+        migrations: &[FileStoreMigration],
+        cipher: Result<Option<StoreCipher>, RippleError>,
+    ) -> FileStore<S> {
+        Self::open_with(
+            path,
+            default,
+            Some(migrations.len() as u32),
+            cipher,
+            |path, cipher| Self::load_versioned_with_cipher(path, migrations, cipher),
+        )
+    }
+
+    fn open_with<F>(
+        path: String,
+        default: S,
+        version: Option<u32>,
+        cipher: Result<Option<StoreCipher>, RippleError>,
+        load: F,
+    ) -> FileStore<S>
+    where
+        F: FnOnce(String, Option<StoreCipher>) -> Result<FileStore<S>, RippleError>,
+    {
+        let mut store = Self::new(path.clone(), default);
+        store.version = version;
+        let cipher = match cipher {
+            Ok(cipher) => cipher,
+            Err(e) => {
+                warn!("Device key unavailable, {} is kept in memory {:?}", path, e);
+                return store.read_only();
+            }
+        };
+        match load(path.clone(), cipher.clone()) {
+            Ok(loaded) => loaded,
+            Err(e) if cipher.is_some() && !matches!(e, RippleError::MissingInput) => {
+                warn!("Unable to decrypt {}, it's kept in memory {:?}", path, e);
+                store.with_cipher(cipher).read_only()
+            }
+            Err(_) => store.with_cipher(cipher),
+        }
+    }
+
+    /// Creates a store which persists the value along with its schema version, see
+    /// [FileStore::load_versioned].
+    pub fn new_versioned(path: String, value: S, version: u32) -> FileStore<S> {
+        let mut store = Self::new(path, value);
+        store.version = Some(version);
+        store
+    }
+
+    fn temp_path(&self) -> String {
+        format!("{}.tmp", self.path)
+    }
+
+    fn backup_path(path: &str) -> String {
+        format!("{}.bak", path)
+    }
+
     fn write_to_disk(&self, value: String) {
-        match OpenOptions::new()
+        let contents = match &self.cipher {
+            Some(cipher) => match cipher.encrypt(value.as_bytes()) {
+                Ok(contents) => contents,
+                Err(e) => {
+                    warn!("Failed to encrypt file store {} {:?}", self.path, e);
+                    return;
+                }
+            },
+            None => value.into_bytes(),
+        };
+        let temp_path = self.temp_path();
+        let result = OpenOptions::new()
             .create(true)
             .write(true)
-            .open(self.path.clone())
-        {
-            Ok(mut file) => {
-                if let Err(e) = file.write_all(value.as_bytes()) {
-                    warn!("Failed to write file store for {:?} {}", e, self.path);
-                }
+            .truncate(true)
+            .open(&temp_path)
+            .and_then(|mut file| {
+                file.write_all(&contents)?;
+                file.sync_all()
+            });
+        if let Err(e) = result {
+            warn!("Failed to write file store for {:?} {}", e, self.path);
+            return;
+        }
+
+        let backup_path = Self::backup_path(&self.path);
+        if Path::new(&self.path).exists() {
+            if let Err(e) = fs::rename(&self.path, &backup_path) {
+                warn!("Failed to backup file store {} {:?}", self.path, e);
             }
-            Err(e) => {
-                warn!("Failed to open file store for {} {:?}", self.path, e);
+        }
+        if let Err(e) = fs::rename(&temp_path, &self.path) {
+            warn!("Failed to replace file store {} {:?}", self.path, e);
+            return;
+        }
+        // Sync the directory so the renames survive a power loss.
+        if let Some(dir) = Path::new(&self.path).parent() {
+            let dir = if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                dir
+            };
+            if let Err(e) = File::open(dir).and_then(|d| d.sync_all()) {
+                debug!(
+                    "Failed to sync directory of file store {} {:?}",
+                    self.path, e
+                );
             }
         }
     }
 
+    /// Rewrites a store loaded from plain json encrypted, the plain backup is removed.
+    fn encrypt_plain_files(&mut self) {
+        self.sync();
+        if let Err(e) = fs::remove_file(Self::backup_path(&self.path)) {
+            warn!("Failed to remove plain backup of {} {:?}", self.path, e);
+        }
+    }
+
     pub fn sync(&mut self) {
-        let new_value_string = serde_json::to_string(&self.value).unwrap();
+        if self.read_only {
+            debug!("file store {} is read only, not syncing", self.path);
+            return;
+        }
+        let new_value_string = match self.version {
+            Some(version) => serde_json::to_string(&json!({
+                SCHEMA_VERSION_KEY: version,
+                SCHEMA_VALUE_KEY: &self.value,
+            })),
+            None => serde_json::to_string(&self.value),
+        }
+        .unwrap();
         self.write_to_disk(new_value_string);
     }
 
@@ -74,17 +242,255 @@ where
         }
     }
 
-    pub fn load(path: String) -> Result<FileStore<S>, RippleError> {
-        if let Ok(contents) = fs::read_to_string(&path) {
-            if let Ok(s) = Self::load_from_content(contents.clone()) {
-                debug!("valid filestore content {} from {}", contents, path);
-                return Ok(FileStore { value: s, path });
-            } else {
-                return Err(RippleError::InvalidAccess);
+    /// Decrypts the file contents, plain json written before encryption was enabled is
+    /// accepted and flagged so it gets encrypted on load.
+    fn decode(contents: Vec<u8>, cipher: Option<&StoreCipher>) -> Option<(String, bool)> {
+        match cipher {
+            Some(cipher) if StoreCipher::is_encrypted(&contents) => cipher
+                .decrypt(&contents)
+                .ok()
+                .and_then(|plain| String::from_utf8(plain).ok())
+                .map(|plain| (plain, false)),
+            Some(_) => String::from_utf8(contents).ok().map(|plain| (plain, true)),
+            None => String::from_utf8(contents).ok().map(|plain| (plain, false)),
+        }
+    }
+
+    /// Reads the store, falling back to the backup of the last good write when the store
+    /// is missing or can't be parsed. Also returns whether the store needs to be rewritten.
+    fn load_with<F>(
+        path: &str,
+        cipher: Option<&StoreCipher>,
+        parse: F,
+    ) -> Result<(S, bool), RippleError>
+    where
+        F: Fn(String) -> Result<S, RippleError>,
+    {
+        let mut found = false;
+        for candidate in [path.to_owned(), Self::backup_path(path)] {
+            if let Ok(contents) = fs::read(&candidate) {
+                found = true;
+                let (contents, unencrypted) = match Self::decode(contents, cipher) {
+                    Some(decoded) => decoded,
+                    None => {
+                        warn!("could not decode file store {}", candidate);
+                        continue;
+                    }
+                };
+                if let Ok(s) = parse(contents.clone()) {
+                    if cipher.is_none() {
+                        debug!("valid filestore content {} from {}", contents, candidate);
+                    }
+                    if candidate != path {
+                        warn!("Recovered file store {} from backup", path);
+                    }
+                    return Ok((s, unencrypted));
+                }
             }
+        }
+        if found {
+            Err(RippleError::InvalidAccess)
         } else {
             info!("No file found in {}", path);
-            return Err(RippleError::MissingInput);
+            Err(RippleError::MissingInput)
         }
     }
+
+    pub fn load(path: String) -> Result<FileStore<S>, RippleError> {
+        Self::load_with_cipher(path, None)
+    }
+
+    /// Loads a store which is encrypted when a cipher is given, see [FileStore::with_cipher].
+    pub fn load_with_cipher(
+        path: String,
+        cipher: Option<StoreCipher>,
+    ) -> Result<FileStore<S>, RippleError> {
+        let (value, unencrypted) =
+            Self::load_with(&path, cipher.as_ref(), Self::load_from_content)?;
+        let mut store = FileStore {
+            value,
+            path,
+            version: None,
+            cipher,
+            read_only: false,
+        };
+        if unencrypted {
+            store.encrypt_plain_files();
+        }
+        Ok(store)
+    }
+
+    /// Loads a store persisted with [FileStore::new_versioned]. The schema version is the
+    /// number of migrations, `migrations[n]` upgrades version `n` to `n + 1` and content
+    /// written without a version is treated as version 0.
+    pub fn load_versioned(
+        path: String,
+        migrations: &[FileStoreMigration],
+    ) -> Result<FileStore<S>, RippleError> {
+        Self::load_versioned_with_cipher(path, migrations, None)
+    }
+
+    pub fn load_versioned_with_cipher(
+        path: String,
+        migrations: &[FileStoreMigration],
+        cipher: Option<StoreCipher>,
+    ) -> Result<FileStore<S>, RippleError> {
+        let version = migrations.len() as u32;
+        let migrated = Cell::new(false);
+        let (value, unencrypted) = Self::load_with(&path, cipher.as_ref(), |contents| {
+            let (stored_version, mut value) = split_versioned(&contents)?;
+            if stored_version > version {
+                warn!(
+                    "file store {} has newer schema version {}",
+                    path, stored_version
+                );
+                return Err(RippleError::ParseError);
+            }
+            for migration in &migrations[stored_version as usize..] {
+                value = migration(value)?;
+            }
+            if stored_version < version {
+                migrated.set(true);
+                debug!(
+                    "migrated file store {} from version {} to {}",
+                    path, stored_version, version
+                );
+            }
+            serde_json::from_value::<S>(value).map_err(|err| {
+                warn!("{:?} could not parse file content", err);
+                RippleError::ParseError
+            })
+        })?;
+        let mut store = FileStore {
+            value,
+            path,
+            version: Some(version),
+            cipher,
+            read_only: false,
+        };
+        if unencrypted {
+            store.encrypt_plain_files();
+        } else if migrated.get() {
+            store.sync();
+        }
+        Ok(store)
+    }
+}
+
+fn split_versioned(contents: &str) -> Result<(u32, Value), RippleError> {
+    let value: Value = serde_json::from_str(contents).map_err(|_| RippleError::ParseError)?;
+    if let Value::Object(map) = &value {
+        if map.len() == 2 {
+            if let (Some(Value::Number(version)), Some(inner)) =
+                (map.get(SCHEMA_VERSION_KEY), map.get(SCHEMA_VALUE_KEY))
+            {
+                if let Some(version) = version.as_u64() {
+                    return Ok((version as u32, inner.clone()));
+                }
+            }
+        }
+    }
+    Ok((0, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use serde_json::{json, Value};
+
+    use crate::{
+        framework::{
+            file_store::FileStore,
+            store_cipher::{DeviceKeyProvider, StoreCipher, DEVICE_KEY_LEN},
+        },
+        utils::error::RippleError,
+    };
+
+    fn test_path(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("file_store_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(name).to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn test_backup_fallback() {
+        let path = test_path("backup");
+        let mut store = FileStore::new(path.clone(), vec![1, 2, 3]);
+        store.sync();
+        store.value = vec![4];
+        store.sync();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[4]");
+
+        fs::write(&path, "[4").unwrap();
+        let store: FileStore<Vec<u32>> = FileStore::load(path).unwrap();
+        assert_eq!(store.value, vec![1, 2, 3]);
+    }
+
+    fn add_count(value: Value) -> Result<Value, RippleError> {
+        Ok(json!({ "names": value, "count": 0 }))
+    }
+
+    #[test]
+    fn test_load_versioned_migrates() {
+        let path = test_path("versioned");
+        fs::write(&path, r#"["a"]"#).unwrap();
+        let store: FileStore<HashMap<String, Value>> =
+            FileStore::load_versioned(path.clone(), &[add_count]).unwrap();
+        assert_eq!(store.value.get("count"), Some(&json!(0)));
+
+        let stored: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(stored.get("schemaVersion"), Some(&json!(1)));
+        let store: FileStore<HashMap<String, Value>> =
+            FileStore::load_versioned(path, &[add_count]).unwrap();
+        assert_eq!(store.value.get("names"), Some(&json!(["a"])));
+    }
+
+    struct TestKeyProvider;
+
+    impl DeviceKeyProvider for TestKeyProvider {
+        fn get_key(&self) -> Result<[u8; DEVICE_KEY_LEN], RippleError> {
+            Ok([7; DEVICE_KEY_LEN])
+        }
+    }
+
+    #[test]
+    fn test_open_keeps_undecryptable_store() {
+        let path = test_path("undecryptable");
+        let cipher = StoreCipher::new(&TestKeyProvider).unwrap();
+        let mut store = FileStore::open(path.clone(), vec![1], Ok(Some(cipher)));
+        store.sync();
+        let contents = fs::read(&path).unwrap();
+
+        let other = StoreCipher::from_hex(&"01".repeat(DEVICE_KEY_LEN)).unwrap();
+        let mut store: FileStore<Vec<u32>> =
+            FileStore::open(path.clone(), Vec::new(), Ok(Some(other)));
+        assert!(store.value.is_empty());
+        store.value.push(2);
+        store.sync();
+        assert_eq!(fs::read(&path).unwrap(), contents);
+
+        let mut store: FileStore<Vec<u32>> =
+            FileStore::open(path.clone(), Vec::new(), Err(RippleError::MissingInput));
+        store.sync();
+        assert_eq!(fs::read(&path).unwrap(), contents);
+    }
+
+    #[test]
+    fn test_encrypts_plain_store() {
+        let path = test_path("encrypted");
+        fs::write(&path, r#"["secret"]"#).unwrap();
+        let cipher = StoreCipher::new(&TestKeyProvider).unwrap();
+        let store: FileStore<Vec<String>> =
+            FileStore::load_with_cipher(path.clone(), Some(cipher.clone())).unwrap();
+        assert_eq!(store.value, vec!["secret".to_owned()]);
+
+        let contents = fs::read(&path).unwrap();
+        assert!(StoreCipher::is_encrypted(&contents));
+        assert!(!String::from_utf8_lossy(&contents).contains("secret"));
+        assert!(FileStore::<Vec<String>>::load(path.clone()).is_err());
+        let store: FileStore<Vec<String>> =
+            FileStore::load_with_cipher(path, Some(cipher)).unwrap();
+        assert_eq!(store.value, vec!["secret".to_owned()]);
+    }
 }
@@ -20,5 +20,6 @@ use crate::utils::error::RippleError;
 pub mod bootstrap;
 pub mod file_store;
 pub mod ripple_contract;
+pub mod store_cipher;
 
 pub type RippleResponse = Result<(), RippleError>;
@@ -71,10 +71,14 @@ pub enum RippleContract {
     DevicePersistence,
     RemoteAccessory,
     Keyboard,
+    /// Provided by an extension answering provider requests like pin and acknowledge challenges
+    /// when no app has registered as provider. Used by [crate::api::firebolt::provider::FallbackProviderRequest]
+    FallbackProvider,
     SessionToken,
     AppEvents,
     DeviceEvents,
     PowerStateEvent,
+    DeviceConditionEvent,
     VoiceGuidance,
     SecureStorage,
     Advertising,
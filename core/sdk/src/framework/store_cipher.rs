@@ -0,0 +1,190 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::{fmt, fs, io::Write, path::Path};
+
+use log::{info, warn};
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+use serde::Deserialize;
+
+use crate::utils::error::RippleError;
+
+pub const DEVICE_KEY_LEN: usize = 32;
+
+/// Prefix of encrypted store files, used to tell them apart from plain json.
+const ENCRYPTED_MAGIC: &[u8] = b"RSE1";
+
+/// Source of the device key used to encrypt persisted stores.
+pub trait DeviceKeyProvider {
+    fn get_key(&self) -> Result<[u8; DEVICE_KEY_LEN], RippleError>;
+}
+
+/// Development key provider which keeps the key in a file, a new key is generated when the
+/// file doesn't exist.
+pub struct FileKeyProvider {
+    path: String,
+}
+
+impl FileKeyProvider {
+    pub fn new(path: String) -> FileKeyProvider {
+        FileKeyProvider { path }
+    }
+
+    fn generate(&self) -> Result<[u8; DEVICE_KEY_LEN], RippleError> {
+        let mut key = [0u8; DEVICE_KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut key)
+            .map_err(|_| RippleError::InvalidOutput)?;
+        let mut options = fs::OpenOptions::new();
+        options.create_new(true).write(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        options
+            .open(&self.path)
+            .and_then(|mut file| {
+                file.write_all(&key)?;
+                file.sync_all()
+            })
+            .map_err(|e| {
+                warn!("Unable to store device key in {} {:?}", self.path, e);
+                RippleError::InvalidAccess
+            })?;
+        info!("Generated device key in {}", self.path);
+        Ok(key)
+    }
+}
+
+impl DeviceKeyProvider for FileKeyProvider {
+    fn get_key(&self) -> Result<[u8; DEVICE_KEY_LEN], RippleError> {
+        if !Path::new(&self.path).exists() {
+            return self.generate();
+        }
+        let contents = fs::read(&self.path).map_err(|_| RippleError::InvalidAccess)?;
+        contents.try_into().map_err(|_| {
+            warn!("Device key in {} has an invalid length", self.path);
+            RippleError::InvalidInput
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DeviceKeyProviderType {
+    File,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct StoreEncryptionConfig {
+    pub key_provider: DeviceKeyProviderType,
+    /// Location of the key for the file provider, relative to the saved dir.
+    #[serde(default = "key_path_default")]
+    pub key_path: String,
+}
+
+fn key_path_default() -> String {
+    "device_key".into()
+}
+
+/// AES-256-GCM encryption of persisted stores.
+#[derive(Clone)]
+pub struct StoreCipher {
+    key: [u8; DEVICE_KEY_LEN],
+}
+
+impl fmt::Debug for StoreCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoreCipher").finish_non_exhaustive()
+    }
+}
+
+impl StoreCipher {
+    pub fn new(provider: &dyn DeviceKeyProvider) -> Result<StoreCipher, RippleError> {
+        Ok(StoreCipher {
+            key: provider.get_key()?,
+        })
+    }
+
+    pub fn from_config(
+        config: &StoreEncryptionConfig,
+        saved_dir: &str,
+    ) -> Result<StoreCipher, RippleError> {
+        match config.key_provider {
+            DeviceKeyProviderType::File => StoreCipher::new(&FileKeyProvider::new(format!(
+                "{}{}",
+                saved_dir, config.key_path
+            ))),
+        }
+    }
+
+    /// Creates the cipher from a hex encoded key.
+    pub fn from_hex(key: &str) -> Result<StoreCipher, RippleError> {
+        if key.len() != DEVICE_KEY_LEN * 2 || !key.is_ascii() {
+            return Err(RippleError::InvalidInput);
+        }
+        let mut bytes = [0u8; DEVICE_KEY_LEN];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&key[i * 2..i * 2 + 2], 16)
+                .map_err(|_| RippleError::InvalidInput)?;
+        }
+        Ok(StoreCipher { key: bytes })
+    }
+
+    fn get_key(&self) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &self.key).unwrap())
+    }
+
+    pub fn is_encrypted(contents: &[u8]) -> bool {
+        contents.starts_with(ENCRYPTED_MAGIC)
+    }
+
+    pub fn encrypt(&self, plain: &[u8]) -> Result<Vec<u8>, RippleError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| RippleError::InvalidOutput)?;
+        let mut in_out = plain.to_vec();
+        self.get_key()
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|_| RippleError::InvalidOutput)?;
+        let mut contents = Vec::with_capacity(ENCRYPTED_MAGIC.len() + NONCE_LEN + in_out.len());
+        contents.extend_from_slice(ENCRYPTED_MAGIC);
+        contents.extend_from_slice(&nonce);
+        contents.extend_from_slice(&in_out);
+        Ok(contents)
+    }
+
+    pub fn decrypt(&self, contents: &[u8]) -> Result<Vec<u8>, RippleError> {
+        if !Self::is_encrypted(contents) || contents.len() < ENCRYPTED_MAGIC.len() + NONCE_LEN {
+            return Err(RippleError::ParseError);
+        }
+        let (nonce, cipher_text) = contents[ENCRYPTED_MAGIC.len()..].split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| RippleError::ParseError)?;
+        let mut in_out = cipher_text.to_vec();
+        let plain = self
+            .get_key()
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| RippleError::ParseError)?;
+        Ok(plain.to_vec())
+    }
+}
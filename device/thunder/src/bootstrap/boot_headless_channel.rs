@@ -0,0 +1,54 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use thunder_ripple_sdk::ripple_sdk::{
+    api::{
+        config::{Config, LauncherConfig},
+        device::device_runtime::RuntimeRegistry,
+        status_update::ExtnStatus,
+    },
+    extn::client::extn_client::ExtnClient,
+    log::{info, warn},
+};
+
+use crate::processors::{
+    headless_window_manager::{
+        HeadlessBrowserRequestProcessor, HeadlessState, HeadlessWindowManagerRequestProcessor,
+    },
+    headless_windows::HeadlessWindowManager,
+};
+
+pub async fn boot_headless_channel(mut client: ExtnClient) {
+    info!("Booting headless window manager");
+    let registry = match client.request(Config::LauncherConfig).await {
+        Ok(message) => match message.payload.extract::<LauncherConfig>() {
+            Some(config) => config.runtime_registry,
+            None => RuntimeRegistry::default(),
+        },
+        Err(e) => {
+            warn!(
+                "launcher config unavailable {:?}, using default runtimes",
+                e
+            );
+            RuntimeRegistry::default()
+        }
+    };
+    let state = HeadlessState::new(client.clone(), HeadlessWindowManager::new(registry));
+    client.add_request_processor(HeadlessBrowserRequestProcessor::new(state.clone()));
+    client.add_request_processor(HeadlessWindowManagerRequestProcessor::new(state));
+    let _ = client.event(ExtnStatus::Ready);
+}
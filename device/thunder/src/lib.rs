@@ -18,5 +18,11 @@
 pub mod thunder_ffi;
 
 pub mod bootstrap {
+    pub mod boot_headless_channel;
     pub mod boot_thunder_channel;
 }
+
+pub mod processors {
+    pub mod headless_window_manager;
+    pub mod headless_windows;
+}
@@ -0,0 +1,166 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use thunder_ripple_sdk::ripple_sdk::{
+    api::device::{device_browser::BrowserRequest, device_window_manager::WindowManagerRequest},
+    async_trait::async_trait,
+    extn::{
+        client::{
+            extn_client::ExtnClient,
+            extn_processor::{
+                DefaultExtnStreamer, ExtnRequestProcessor, ExtnStreamProcessor, ExtnStreamer,
+            },
+        },
+        extn_client_message::{ExtnMessage, ExtnResponse},
+    },
+    tokio::sync::mpsc,
+    utils::error::RippleError,
+};
+
+use crate::processors::headless_windows::HeadlessWindowManager;
+
+#[derive(Debug, Clone)]
+pub struct HeadlessState {
+    client: ExtnClient,
+    window_manager: HeadlessWindowManager,
+}
+
+impl HeadlessState {
+    pub fn new(client: ExtnClient, window_manager: HeadlessWindowManager) -> HeadlessState {
+        HeadlessState {
+            client,
+            window_manager,
+        }
+    }
+
+    pub fn get_client(&self) -> ExtnClient {
+        self.client.clone()
+    }
+}
+
+async fn respond<P: ExtnRequestProcessor>(
+    client: ExtnClient,
+    msg: ExtnMessage,
+    result: Result<ExtnResponse, RippleError>,
+) -> bool {
+    match result {
+        Ok(response) => P::respond(client, msg, response).await.is_ok(),
+        Err(e) => P::handle_error(client, msg, e).await,
+    }
+}
+
+/// Fulfills the [BrowserRequest] contract with a [HeadlessWindowManager].
+#[derive(Debug)]
+pub struct HeadlessBrowserRequestProcessor {
+    state: HeadlessState,
+    streamer: DefaultExtnStreamer,
+}
+
+impl HeadlessBrowserRequestProcessor {
+    pub fn new(state: HeadlessState) -> HeadlessBrowserRequestProcessor {
+        HeadlessBrowserRequestProcessor {
+            state,
+            streamer: DefaultExtnStreamer::new(),
+        }
+    }
+}
+
+impl ExtnStreamProcessor for HeadlessBrowserRequestProcessor {
+    type STATE = HeadlessState;
+    type VALUE = BrowserRequest;
+
+    fn get_state(&self) -> Self::STATE {
+        self.state.clone()
+    }
+
+    fn receiver(&mut self) -> mpsc::Receiver<ExtnMessage> {
+        self.streamer.receiver()
+    }
+
+    fn sender(&self) -> mpsc::Sender<ExtnMessage> {
+        self.streamer.sender()
+    }
+}
+
+#[async_trait]
+impl ExtnRequestProcessor for HeadlessBrowserRequestProcessor {
+    fn get_client(&self) -> ExtnClient {
+        self.state.get_client()
+    }
+
+    async fn process_request(
+        state: Self::STATE,
+        msg: ExtnMessage,
+        extracted_message: Self::VALUE,
+    ) -> bool {
+        let result = state
+            .window_manager
+            .handle_browser_request(extracted_message);
+        respond::<Self>(state.get_client(), msg, result).await
+    }
+}
+
+/// Fulfills the [WindowManagerRequest] contract with a [HeadlessWindowManager].
+#[derive(Debug)]
+pub struct HeadlessWindowManagerRequestProcessor {
+    state: HeadlessState,
+    streamer: DefaultExtnStreamer,
+}
+
+impl HeadlessWindowManagerRequestProcessor {
+    pub fn new(state: HeadlessState) -> HeadlessWindowManagerRequestProcessor {
+        HeadlessWindowManagerRequestProcessor {
+            state,
+            streamer: DefaultExtnStreamer::new(),
+        }
+    }
+}
+
+impl ExtnStreamProcessor for HeadlessWindowManagerRequestProcessor {
+    type STATE = HeadlessState;
+    type VALUE = WindowManagerRequest;
+
+    fn get_state(&self) -> Self::STATE {
+        self.state.clone()
+    }
+
+    fn receiver(&mut self) -> mpsc::Receiver<ExtnMessage> {
+        self.streamer.receiver()
+    }
+
+    fn sender(&self) -> mpsc::Sender<ExtnMessage> {
+        self.streamer.sender()
+    }
+}
+
+#[async_trait]
+impl ExtnRequestProcessor for HeadlessWindowManagerRequestProcessor {
+    fn get_client(&self) -> ExtnClient {
+        self.state.get_client()
+    }
+
+    async fn process_request(
+        state: Self::STATE,
+        msg: ExtnMessage,
+        extracted_message: Self::VALUE,
+    ) -> bool {
+        let result = state
+            .window_manager
+            .handle_window_manager_request(extracted_message);
+        respond::<Self>(state.get_client(), msg, result).await
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+
+use std::sync::{Arc, RwLock};
+
+use thunder_ripple_sdk::ripple_sdk::{
+    api::{
+        apps::Dimensions,
+        device::{
+            device_browser::{BrowserLaunchParams, BrowserRequest},
+            device_runtime::RuntimeRegistry,
+            device_window_manager::WindowManagerRequest,
+        },
+    },
+    extn::extn_client_message::ExtnResponse,
+    utils::error::RippleError,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadlessClient {
+    pub name: String,
+    pub runtime: String,
+    pub uri: String,
+    pub visible: bool,
+    pub suspended: bool,
+    pub dimensions: Dimensions,
+}
+
+impl From<BrowserLaunchParams> for HeadlessClient {
+    fn from(params: BrowserLaunchParams) -> Self {
+        HeadlessClient {
+            name: params.browser_name.to_lowercase(),
+            runtime: params.runtime,
+            uri: params.uri,
+            visible: params.visible,
+            suspended: params.suspend,
+            dimensions: Dimensions {
+                x: params.x,
+                y: params.y,
+                w: params.w,
+                h: params.h,
+            },
+        }
+    }
+}
+
+/// In-memory window manager which fulfills the [BrowserRequest] and [WindowManagerRequest]
+/// contracts without a display. Useful for tests and for devices running apps without
+/// graphics. Client names are case insensitive, like they are in RDKShell.
+#[derive(Debug, Clone, Default)]
+pub struct HeadlessWindowManager {
+    registry: RuntimeRegistry,
+    /// Loaded clients ordered from back to front.
+    clients: Arc<RwLock<Vec<HeadlessClient>>>,
+    focused: Arc<RwLock<Option<String>>>,
+}
+
+impl HeadlessWindowManager {
+    pub fn new(registry: RuntimeRegistry) -> HeadlessWindowManager {
+        HeadlessWindowManager {
+            registry,
+            ..Default::default()
+        }
+    }
+
+    pub fn get_client(&self, name: &str) -> Option<HeadlessClient> {
+        let name = name.to_lowercase();
+        self.clients
+            .read()
+            .unwrap()
+            .iter()
+            .find(|c| c.name == name)
+            .cloned()
+    }
+
+    pub fn get_focused(&self) -> Option<String> {
+        self.focused.read().unwrap().clone()
+    }
+
+    /// Client names ordered from front to back.
+    pub fn get_clients(&self) -> Vec<String> {
+        self.clients
+            .read()
+            .unwrap()
+            .iter()
+            .rev()
+            .map(|c| c.name.clone())
+            .collect()
+    }
+
+    fn position(&self, name: &str) -> Result<usize, RippleError> {
+        let name = name.to_lowercase();
+        self.clients
+            .read()
+            .unwrap()
+            .iter()
+            .position(|c| c.name == name)
+            .ok_or(RippleError::InvalidInput)
+    }
+
+    fn update<F>(&self, name: &str, f: F) -> Result<ExtnResponse, RippleError>
+    where
+        F: FnOnce(&mut HeadlessClient),
+    {
+        let index = self.position(name)?;
+        f(&mut self.clients.write().unwrap()[index]);
+        Ok(ExtnResponse::None(()))
+    }
+
+    fn move_to(&self, name: &str, front: bool) -> Result<ExtnResponse, RippleError> {
+        let index = self.position(name)?;
+        let mut clients = self.clients.write().unwrap();
+        let client = clients.remove(index);
+        if front {
+            clients.push(client);
+        } else {
+            clients.insert(0, client);
+        }
+        Ok(ExtnResponse::None(()))
+    }
+
+    pub fn handle_browser_request(
+        &self,
+        request: BrowserRequest,
+    ) -> Result<ExtnResponse, RippleError> {
+        match request {
+            BrowserRequest::Start(params) => {
+                if !self.registry.supports(&params.runtime) {
+                    return Err(RippleError::InvalidInput);
+                }
+                let focused = params.focused;
+                let client = HeadlessClient::from(params);
+                let name = client.name.clone();
+                {
+                    let mut clients = self.clients.write().unwrap();
+                    clients.retain(|c| c.name != name);
+                    clients.push(client);
+                }
+                if focused {
+                    let _ = self.focused.write().unwrap().insert(name);
+                }
+                Ok(ExtnResponse::None(()))
+            }
+            BrowserRequest::Destroy(params) => {
+                let index = self.position(&params.browser_name)?;
+                let removed = self.clients.write().unwrap().remove(index);
+                let mut focused = self.focused.write().unwrap();
+                if focused.as_ref() == Some(&removed.name) {
+                    *focused = None;
+                }
+                Ok(ExtnResponse::None(()))
+            }
+            BrowserRequest::GetBrowserName(params) => match self.registry.supports(&params.runtime)
+            {
+                true => Ok(ExtnResponse::String(format!(
+                    "{}-{}",
+                    params.runtime, params.name
+                ))),
+                false => Err(RippleError::InvalidInput),
+            },
+        }
+    }
+
+    pub fn handle_window_manager_request(
+        &self,
+        request: WindowManagerRequest,
+    ) -> Result<ExtnResponse, RippleError> {
+        match request {
+            WindowManagerRequest::Visibility(name, visible) => {
+                self.update(&name, |c| c.visible = visible)
+            }
+            WindowManagerRequest::Dimensions(name, dimensions) => {
+                self.update(&name, |c| c.dimensions = dimensions)
+            }
+            WindowManagerRequest::MoveToFront(name) => self.move_to(&name, true),
+            WindowManagerRequest::MoveToBack(name) => self.move_to(&name, false),
+            WindowManagerRequest::Focus(name) => {
+                self.position(&name)?;
+                let _ = self.focused.write().unwrap().insert(name.to_lowercase());
+                Ok(ExtnResponse::None(()))
+            }
+            WindowManagerRequest::GetClients => Ok(ExtnResponse::List(self.get_clients())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use thunder_ripple_sdk::ripple_sdk::api::device::{
+        device_browser::{BrowserDestroyParams, BrowserLaunchParams, BrowserRequest},
+        device_runtime::RuntimeRegistry,
+        device_window_manager::WindowManagerRequest,
+    };
+
+    use super::HeadlessWindowManager;
+
+    fn start(wm: &HeadlessWindowManager, name: &str, runtime: &str) -> bool {
+        wm.handle_browser_request(BrowserRequest::Start(BrowserLaunchParams {
+            uri: "https://example.com".into(),
+            browser_name: name.into(),
+            runtime: runtime.into(),
+            resident: false,
+            visible: false,
+            suspend: false,
+            focused: true,
+            name: name.into(),
+            x: 0,
+            y: 0,
+            w: 1920,
+            h: 1080,
+            properties: None,
+        }))
+        .is_ok()
+    }
+
+    #[test]
+    fn test_start_and_destroy() {
+        let wm = HeadlessWindowManager::new(RuntimeRegistry::default());
+        assert!(start(&wm, "Html-0", "web"));
+        assert!(!start(&wm, "Native-0", "native"));
+        assert_eq!(wm.get_focused(), Some("html-0".into()));
+        assert!(wm
+            .handle_browser_request(BrowserRequest::Destroy(BrowserDestroyParams {
+                browser_name: "Html-0".into(),
+            }))
+            .is_ok());
+        assert!(wm.get_clients().is_empty());
+        assert!(wm.get_focused().is_none());
+    }
+
+    #[test]
+    fn test_window_operations() {
+        let wm = HeadlessWindowManager::new(RuntimeRegistry::default());
+        assert!(start(&wm, "first", "web"));
+        assert!(start(&wm, "second", "lightning"));
+        assert_eq!(wm.get_clients(), vec!["second", "first"]);
+
+        assert!(wm
+            .handle_window_manager_request(WindowManagerRequest::MoveToFront("first".into()))
+            .is_ok());
+        assert_eq!(wm.get_clients(), vec!["first", "second"]);
+        assert!(wm
+            .handle_window_manager_request(WindowManagerRequest::Visibility("first".into(), true))
+            .is_ok());
+        assert!(wm.get_client("first").unwrap().visible);
+        assert!(wm
+            .handle_window_manager_request(WindowManagerRequest::Focus("second".into()))
+            .is_ok());
+        assert_eq!(wm.get_focused(), Some("second".into()));
+        assert!(wm
+            .handle_window_manager_request(WindowManagerRequest::Focus("missing".into()))
+            .is_err());
+    }
+}
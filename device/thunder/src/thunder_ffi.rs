@@ -34,7 +34,9 @@ use thunder_ripple_sdk::ripple_sdk::{
     utils::{error::RippleError, logger::init_logger},
 };
 
-use crate::bootstrap::boot_thunder_channel::boot_thunder_channel;
+use crate::bootstrap::{
+    boot_headless_channel::boot_headless_channel, boot_thunder_channel::boot_thunder_channel,
+};
 
 fn init_library() -> CExtnMetadata {
     let _ = init_logger("device_channel".into());
@@ -51,11 +53,17 @@ fn init_library() -> CExtnMetadata {
         ]),
         Version::new(1, 1, 0),
     );
+    // Selected through the extension manifest in place of the thunder window manager and browser
+    let headless_channel_meta = ExtnSymbolMetadata::get(
+        ExtnId::new_channel(ExtnClassId::Device, "headless".into()),
+        ContractFulfiller::new(vec![RippleContract::WindowManager, RippleContract::Browser]),
+        Version::new(1, 1, 0),
+    );
 
     debug!("Returning thunder library entries");
     let extn_metadata = ExtnMetadata {
         name: "thunder".into(),
-        symbols: vec![thunder_channel_meta],
+        symbols: vec![thunder_channel_meta, headless_channel_meta],
     };
     extn_metadata.into()
 }
@@ -74,12 +82,29 @@ pub fn start(sender: ExtnSender, receiver: CReceiver<CExtnMessage>) {
     });
 }
 
+pub fn start_headless(sender: ExtnSender, receiver: CReceiver<CExtnMessage>) {
+    let _ = init_logger("headless_channel".into());
+    info!("Starting headless channel");
+    let runtime = Runtime::new().unwrap();
+    let client = ExtnClient::new(receiver.clone(), sender);
+    runtime.block_on(async move {
+        let client_for_receiver = client.clone();
+        tokio::spawn(async move { boot_headless_channel(client).await });
+        client_for_receiver.initialize().await;
+    });
+}
+
 fn build(extn_id: String) -> Result<Box<ExtnChannel>, RippleError> {
     if let Ok(id) = ExtnId::try_from(extn_id.clone()) {
         let current_id = ExtnId::new_channel(ExtnClassId::Device, "thunder".into());
+        let headless_id = ExtnId::new_channel(ExtnClassId::Device, "headless".into());
 
         if id.eq(&current_id) {
-            return Ok(Box::new(ExtnChannel { start }));
+            Ok(Box::new(ExtnChannel { start }))
+        } else if id.eq(&headless_id) {
+            Ok(Box::new(ExtnChannel {
+                start: start_headless,
+            }))
         } else {
             Err(RippleError::ExtnError)
         }
@@ -25,7 +25,9 @@ use ripple_sdk::{
     api::{
         apps::{AppEvent, AppEventRequest},
         device::{
-            device_events::{DeviceEvent, DeviceEventCallback},
+            device_events::{
+                DeviceCondition, DeviceConditionEvent, DeviceEvent, DeviceEventCallback,
+            },
             device_operator::DeviceSubscribeRequest,
             device_request::{
                 AudioProfile, NetworkResponse, NetworkState, NetworkType, PowerState,
@@ -70,6 +72,7 @@ pub enum ThunderEventMessage {
     PowerState(SystemPowerState),
     VoiceGuidance(VoiceGuidanceEvent),
     Audio(HashMap<AudioProfile, bool>),
+    DeviceCondition(DeviceConditionEvent),
 }
 impl ThunderEventMessage {
     pub fn get(event: &str, value: &Value) -> Option<Self> {
@@ -125,6 +128,30 @@ impl ThunderEventMessage {
                         value.clone(),
                     )))
                 }
+                DeviceEvent::HdmiConnectionChanged => {
+                    if let Some(displays) = value["connectedVideoDisplays"].as_array() {
+                        return Some(ThunderEventMessage::DeviceCondition(DeviceConditionEvent {
+                            condition: DeviceCondition::HdmiConnected,
+                            active: !displays.is_empty(),
+                        }));
+                    }
+                }
+                DeviceEvent::RemotePairingChanged => {
+                    if let Some(remotes) = value["status"]["remoteData"].as_array() {
+                        return Some(ThunderEventMessage::DeviceCondition(DeviceConditionEvent {
+                            condition: DeviceCondition::RemotePaired,
+                            active: !remotes.is_empty(),
+                        }));
+                    }
+                }
+                DeviceEvent::TextToSpeechStateChanged => {
+                    if let Some(active) = value["state"].as_bool() {
+                        return Some(ThunderEventMessage::DeviceCondition(DeviceConditionEvent {
+                            condition: DeviceCondition::TextToSpeechActive,
+                            active,
+                        }));
+                    }
+                }
             }
         }
         None
@@ -209,11 +236,17 @@ impl ThunderEventHandler {
     }
 
     pub fn callback_device_event(state: ThunderState, event_name: String, event: ExtnEvent) {
-        if state.event_processor.check_last_event(&event_name, &event) {
+        // App and extension events of the same device event are deduplicated separately
+        let event_name = match event {
+            ExtnEvent::AppEvent(_) => event_name,
+            _ => format!("{}.extn", event_name),
+        };
+        if !state.event_processor.check_last_event(&event_name, &event) {
             state.event_processor.add_last_event(&event_name, &event);
             if let Err(_) = match event {
                 ExtnEvent::AppEvent(a) => state.get_client().request_transient(a),
                 ExtnEvent::PowerState(p) => state.get_client().request_transient(p),
+                ExtnEvent::DeviceCondition(c) => state.get_client().request_transient(c),
                 _ => Err(RippleError::InvalidOutput),
             } {
                 error!("Error while forwarding app event");
@@ -229,10 +262,15 @@ pub trait DeviceSubscribeRequestProvider {
     fn get_handler(&self) -> fn(state: ThunderState, value: Value);
 }
 
+type ListenerCallbacks = HashMap<String, Vec<(String, DeviceEventCallback)>>;
+
 #[derive(Debug, Clone)]
 pub struct ThunderEventProcessor {
     event_map: Arc<RwLock<HashMap<String, ThunderEventHandler>>>,
     last_event: Arc<RwLock<HashMap<String, Value>>>,
+    /// Listeners of each event with the callback they asked for, a single handler serves
+    /// both the app and the extension listeners of an event.
+    listener_callbacks: Arc<RwLock<ListenerCallbacks>>,
 }
 
 impl ThunderEventProcessor {
@@ -240,7 +278,21 @@ impl ThunderEventProcessor {
         ThunderEventProcessor {
             event_map: Arc::new(RwLock::new(HashMap::new())),
             last_event: Arc::new(RwLock::new(HashMap::new())),
+            listener_callbacks: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Returns the distinct callbacks requested by the listeners of the event.
+    pub fn get_callback_types(&self, event: &str) -> Vec<DeviceEventCallback> {
+        let mut callback_types = Vec::new();
+        if let Some(listeners) = self.listener_callbacks.read().unwrap().get(event) {
+            for (_, callback_type) in listeners {
+                if !callback_types.contains(callback_type) {
+                    callback_types.push(callback_type.clone());
+                }
+            }
         }
+        callback_types
     }
 
     pub fn get_handler(&self, event: &str) -> Option<ThunderEventHandler> {
@@ -263,6 +315,12 @@ impl ThunderEventProcessor {
 
     pub fn add_event_listener(&self, app_id: String, handler: ThunderEventHandler) -> bool {
         let event_name = handler.get_id();
+        self.listener_callbacks
+            .write()
+            .unwrap()
+            .entry(event_name.clone())
+            .or_default()
+            .push((app_id.clone(), handler.callback_type.clone()));
         let mut event_map = self.event_map.write().unwrap();
         if let Some(entry) = event_map.get_mut(&event_name) {
             entry.add_listener(app_id);
@@ -274,6 +332,14 @@ impl ThunderEventProcessor {
     }
 
     pub fn remove_event_listener(&self, event_name: String, app_id: String) -> bool {
+        if let Some(listeners) = self
+            .listener_callbacks
+            .write()
+            .unwrap()
+            .get_mut(&event_name)
+        {
+            listeners.retain(|(id, _)| !id.eq(&app_id));
+        }
         let mut event_map = self.event_map.write().unwrap();
         if let Some(entry) = event_map.get_mut(&event_name) {
             if !entry.remove_listener(app_id) {
@@ -301,3 +367,41 @@ impl ThunderEventProcessor {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ripple_sdk::api::device::device_events::DeviceEventCallback;
+
+    use crate::{
+        events::thunder_event_processor::{ThunderEventHandlerProvider, ThunderEventProcessor},
+        processors::events::thunder_event_handlers::NetworkEventHandler,
+    };
+
+    #[test]
+    fn test_handler_serves_both_callbacks() {
+        let processor = ThunderEventProcessor::new();
+        let event = NetworkEventHandler::get_mapped_event();
+        assert!(processor.add_event_listener(
+            "ripple".into(),
+            NetworkEventHandler::provide("ripple".into(), DeviceEventCallback::ExtnEvent),
+        ));
+        assert!(!processor.add_event_listener(
+            "app".into(),
+            NetworkEventHandler::provide("app".into(), DeviceEventCallback::FireboltAppEvent),
+        ));
+        assert_eq!(
+            processor.get_callback_types(&event),
+            vec![
+                DeviceEventCallback::ExtnEvent,
+                DeviceEventCallback::FireboltAppEvent
+            ]
+        );
+
+        assert!(!processor.remove_event_listener(event.clone(), "app".into()));
+        assert_eq!(
+            processor.get_callback_types(&event),
+            vec![DeviceEventCallback::ExtnEvent]
+        );
+        assert!(processor.get_handler(&event).is_some());
+    }
+}
@@ -27,12 +27,14 @@ use crate::{
             apps::{AppEvent, AppEventRequest},
             device::{
                 device_events::{
-                    DeviceEventCallback, HDCP_CHANGED_EVENT, HDR_CHANGED_EVENT,
-                    NETWORK_CHANGED_EVENT, POWER_STATE_CHANGED, SCREEN_RESOLUTION_CHANGED_EVENT,
-                    VIDEO_RESOLUTION_CHANGED_EVENT,
+                    DeviceCondition, DeviceConditionEvent, DeviceEventCallback, HDCP_CHANGED_EVENT,
+                    HDMI_CONNECTION_CHANGED, HDR_CHANGED_EVENT, NETWORK_CHANGED_EVENT,
+                    POWER_STATE_CHANGED, REMOTE_PAIRING_CHANGED, SCREEN_RESOLUTION_CHANGED_EVENT,
+                    TEXT_TO_SPEECH_STATE_CHANGED, VIDEO_RESOLUTION_CHANGED_EVENT,
                 },
                 device_request::{
-                    AudioProfile, HdcpProfile, HdrProfile, NetworkResponse, SystemPowerState,
+                    AudioProfile, HdcpProfile, HdrProfile, NetworkResponse, NetworkState,
+                    SystemPowerState,
                 },
             },
         },
@@ -308,6 +310,28 @@ impl ThunderEventHandlerProvider for NetworkEventHandler {
     fn module() -> String {
         ThunderPlugin::Network.callsign_string()
     }
+
+    fn get_extn_event(
+        r: Self::EVENT,
+        callback_type: DeviceEventCallback,
+    ) -> Result<ExtnEvent, RippleError> {
+        match callback_type {
+            DeviceEventCallback::FireboltAppEvent => {
+                Ok(ExtnEvent::AppEvent(AppEventRequest::Emit(AppEvent {
+                    event_name: Self::get_mapped_event(),
+                    context: None,
+                    result: serde_json::to_value(r).unwrap(),
+                    app_id: None,
+                })))
+            }
+            DeviceEventCallback::ExtnEvent => {
+                Ok(ExtnEvent::DeviceCondition(DeviceConditionEvent {
+                    condition: DeviceCondition::NetworkConnected,
+                    active: r.state == NetworkState::Connected,
+                }))
+            }
+        }
+    }
 }
 
 // -----------------------
@@ -480,3 +504,190 @@ impl ThunderEventHandlerProvider for AudioChangedEvent {
         ThunderPlugin::DisplaySettings.callsign_string()
     }
 }
+
+// -----------------------
+// Device conditions which affect capability availability
+
+pub fn is_device_condition(value: ThunderEventMessage) -> bool {
+    if let ThunderEventMessage::DeviceCondition(_) = value {
+        return true;
+    }
+    false
+}
+
+fn get_device_condition_event(
+    event_name: String,
+    r: DeviceConditionEvent,
+    callback_type: DeviceEventCallback,
+) -> ExtnEvent {
+    match callback_type {
+        DeviceEventCallback::FireboltAppEvent => {
+            ExtnEvent::AppEvent(AppEventRequest::Emit(AppEvent {
+                event_name,
+                context: None,
+                result: serde_json::to_value(r.active).unwrap(),
+                app_id: None,
+            }))
+        }
+        DeviceEventCallback::ExtnEvent => ExtnEvent::DeviceCondition(r),
+    }
+}
+
+pub struct HdmiConnectionEventHandler;
+
+impl HdmiConnectionEventHandler {
+    pub fn handle(
+        state: ThunderState,
+        value: ThunderEventMessage,
+        callback_type: DeviceEventCallback,
+    ) {
+        if let ThunderEventMessage::DeviceCondition(c) = value {
+            if let Ok(v) = Self::get_extn_event(c, callback_type) {
+                ThunderEventHandler::callback_device_event(state, Self::get_mapped_event(), v)
+            }
+        }
+    }
+}
+
+impl ThunderEventHandlerProvider for HdmiConnectionEventHandler {
+    type EVENT = DeviceConditionEvent;
+    fn provide(id: String, callback_type: DeviceEventCallback) -> ThunderEventHandler {
+        ThunderEventHandler {
+            request: Self::get_device_request(),
+            handle: Self::handle,
+            is_valid: is_device_condition,
+            listeners: vec![id],
+            id: Self::get_mapped_event(),
+            callback_type,
+        }
+    }
+
+    fn event_name() -> String {
+        "connectedVideoDisplaysUpdated".into()
+    }
+
+    fn get_mapped_event() -> String {
+        HDMI_CONNECTION_CHANGED.into()
+    }
+
+    fn module() -> String {
+        ThunderPlugin::DisplaySettings.callsign_string()
+    }
+
+    fn get_extn_event(
+        r: Self::EVENT,
+        callback_type: DeviceEventCallback,
+    ) -> Result<ExtnEvent, RippleError> {
+        Ok(get_device_condition_event(
+            Self::get_mapped_event(),
+            r,
+            callback_type,
+        ))
+    }
+}
+
+pub struct RemotePairingEventHandler;
+
+impl RemotePairingEventHandler {
+    pub fn handle(
+        state: ThunderState,
+        value: ThunderEventMessage,
+        callback_type: DeviceEventCallback,
+    ) {
+        if let ThunderEventMessage::DeviceCondition(c) = value {
+            if let Ok(v) = Self::get_extn_event(c, callback_type) {
+                ThunderEventHandler::callback_device_event(state, Self::get_mapped_event(), v)
+            }
+        }
+    }
+}
+
+impl ThunderEventHandlerProvider for RemotePairingEventHandler {
+    type EVENT = DeviceConditionEvent;
+    fn provide(id: String, callback_type: DeviceEventCallback) -> ThunderEventHandler {
+        ThunderEventHandler {
+            request: Self::get_device_request(),
+            handle: Self::handle,
+            is_valid: is_device_condition,
+            listeners: vec![id],
+            id: Self::get_mapped_event(),
+            callback_type,
+        }
+    }
+
+    fn event_name() -> String {
+        "onStatus".into()
+    }
+
+    fn get_mapped_event() -> String {
+        REMOTE_PAIRING_CHANGED.into()
+    }
+
+    fn module() -> String {
+        ThunderPlugin::RemoteControl.callsign_string()
+    }
+
+    fn get_extn_event(
+        r: Self::EVENT,
+        callback_type: DeviceEventCallback,
+    ) -> Result<ExtnEvent, RippleError> {
+        Ok(get_device_condition_event(
+            Self::get_mapped_event(),
+            r,
+            callback_type,
+        ))
+    }
+}
+
+pub struct TextToSpeechStateEventHandler;
+
+impl TextToSpeechStateEventHandler {
+    pub fn handle(
+        state: ThunderState,
+        value: ThunderEventMessage,
+        callback_type: DeviceEventCallback,
+    ) {
+        if let ThunderEventMessage::DeviceCondition(c) = value {
+            if let Ok(v) = Self::get_extn_event(c, callback_type) {
+                ThunderEventHandler::callback_device_event(state, Self::get_mapped_event(), v)
+            }
+        }
+    }
+}
+
+impl ThunderEventHandlerProvider for TextToSpeechStateEventHandler {
+    type EVENT = DeviceConditionEvent;
+    fn provide(id: String, callback_type: DeviceEventCallback) -> ThunderEventHandler {
+        ThunderEventHandler {
+            request: Self::get_device_request(),
+            handle: Self::handle,
+            is_valid: is_device_condition,
+            listeners: vec![id],
+            id: Self::get_mapped_event(),
+            callback_type,
+        }
+    }
+
+    fn event_name() -> String {
+        "onttsstatechanged".into()
+    }
+
+    fn get_mapped_event() -> String {
+        TEXT_TO_SPEECH_STATE_CHANGED.into()
+    }
+
+    fn module() -> String {
+        ThunderPlugin::TextToSpeech.callsign_string()
+    }
+
+    fn get_extn_event(
+        r: Self::EVENT,
+        callback_type: DeviceEventCallback,
+    ) -> Result<ExtnEvent, RippleError> {
+        Ok(get_device_condition_event(
+            Self::get_mapped_event(),
+            r,
+            callback_type,
+        ))
+    }
+}
@@ -21,6 +21,7 @@ use ripple_sdk::{
             BrowserDestroyParams, BrowserLaunchParams, BrowserNameRequestParams, BrowserRequest,
         },
         device_operator::{DeviceCallRequest, DeviceChannelParams, DeviceOperator},
+        device_runtime::{LIGHTNING_RUNTIME, WEB_RUNTIME},
     },
     async_trait::async_trait,
     extn::{
@@ -97,11 +98,31 @@ impl ThunderBrowserRequestProcessor {
         Ok(())
     }
 
+    /// Maps a runtime onto the RDKShell client type which hosts it.
+    fn get_rdkshell_type(launch_params: &BrowserLaunchParams) -> Option<String> {
+        match launch_params.runtime.as_str() {
+            WEB_RUNTIME => Some("HtmlApp".into()),
+            LIGHTNING_RUNTIME => match launch_params.resident {
+                true => Some("ResidentApp".into()),
+                false => Some("LightningApp".into()),
+            },
+            _ => None,
+        }
+    }
+
     async fn start(
         state: ThunderState,
         launch_params: BrowserLaunchParams,
         req: ExtnMessage,
     ) -> bool {
+        let rdkshell_type = match Self::get_rdkshell_type(&launch_params) {
+            Some(t) => t,
+            None => {
+                error!("Unsupported runtime {}", launch_params.runtime);
+                return Self::handle_error(state.get_client(), req, RippleError::InvalidInput)
+                    .await;
+            }
+        };
         let thunder_method = ThunderPlugin::RDKShell.method("launch");
         let client = state.get_thunder_client();
         let browser_name = launch_params.browser_name.clone();
@@ -109,7 +130,7 @@ impl ThunderBrowserRequestProcessor {
         // check object integrity
         let r = RDKShellLaunchRequest {
             callsign: launch_params.browser_name,
-            _type: launch_params._type,
+            _type: rdkshell_type,
             suspend: launch_params.suspend,
             uri: launch_params.uri,
             visible: launch_params.visible,
@@ -181,8 +202,8 @@ impl ThunderBrowserRequestProcessor {
         req: ExtnMessage,
     ) -> bool {
         let browser_name = match bnr.runtime.as_str() {
-            "web" => Some(format!("Html-{}", bnr.instances)),
-            "lightning" => Some(format!("FireboltMainApp-{}", bnr.name)),
+            WEB_RUNTIME => Some(format!("Html-{}", bnr.instances)),
+            LIGHTNING_RUNTIME => Some(format!("FireboltMainApp-{}", bnr.name)),
             _ => None,
         };
         if let None = browser_name {
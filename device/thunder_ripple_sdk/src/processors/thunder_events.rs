@@ -35,8 +35,9 @@ use crate::{
 };
 
 use super::events::thunder_event_handlers::{
-    AudioChangedEvent, HDCPEventHandler, HDREventHandler, NetworkEventHandler,
-    ScreenResolutionEventHandler, SystemPowerStateChangeEventHandler, VideoResolutionEventHandler,
+    AudioChangedEvent, HDCPEventHandler, HDREventHandler, HdmiConnectionEventHandler,
+    NetworkEventHandler, RemotePairingEventHandler, ScreenResolutionEventHandler,
+    SystemPowerStateChangeEventHandler, TextToSpeechStateEventHandler, VideoResolutionEventHandler,
     VoiceGuidanceEnabledChangedEventHandler,
 };
 
@@ -128,6 +129,21 @@ impl ExtnRequestProcessor for ThunderOpenEventsProcessor {
                 id.clone(),
                 VoiceGuidanceEnabledChangedEventHandler::provide(id, callback_type),
             ),
+            DeviceEvent::HdmiConnectionChanged => state.handle_listener(
+                listen,
+                id.clone(),
+                HdmiConnectionEventHandler::provide(id, callback_type),
+            ),
+            DeviceEvent::RemotePairingChanged => state.handle_listener(
+                listen,
+                id.clone(),
+                RemotePairingEventHandler::provide(id, callback_type),
+            ),
+            DeviceEvent::TextToSpeechStateChanged => state.handle_listener(
+                listen,
+                id.clone(),
+                TextToSpeechStateEventHandler::provide(id, callback_type),
+            ),
         };
         v.await;
         Self::ack(state.get_client(), msg).await.is_ok()
@@ -21,7 +21,8 @@ use crate::{
         api::device::{
             device_operator::{DeviceCallRequest, DeviceChannelParams, DeviceOperator},
             device_peristence::{
-                DevicePersistenceRequest, GetStorageProperty, SetStorageProperty, StorageData,
+                DeleteStorageProperty, DevicePersistenceRequest, GetStorageProperty,
+                SetStorageProperty, StorageData,
             },
         },
         async_trait::async_trait,
@@ -30,7 +31,7 @@ use crate::{
             client::extn_processor::{
                 DefaultExtnStreamer, ExtnRequestProcessor, ExtnStreamProcessor, ExtnStreamer,
             },
-            extn_client_message::{ExtnMessage, ExtnRequest, ExtnResponse},
+            extn_client_message::{ExtnMessage, ExtnResponse},
         },
         log::{debug, error, info},
         serde_json::{self, json, Value},
@@ -59,18 +60,6 @@ pub struct ThunderStorageRequestProcessor {
     streamer: DefaultExtnStreamer,
 }
 
-#[async_trait]
-pub trait StorageService {
-    async fn delete_key(self: Box<Self>, namespace: String, key: String) -> bool;
-    async fn delete_namespace(self: Box<Self>, namespace: String) -> bool;
-    async fn flush_cache(self: Box<Self>) -> bool;
-    // async fn get_keys(self: Box<Self>, namespace: String) -> (Vec<String>, bool);
-    // async fn get_namespaces(self: Box<Self>) -> (Vec<String>, bool);
-    // async fn get_storage_size(self: Box<Self>) -> (HashMap<String, u32>, bool);
-    async fn get_value(state: ThunderState, req: ExtnMessage, data: GetStorageProperty) -> bool;
-    async fn set_value(state: ThunderState, req: ExtnRequest, data: SetStorageProperty) -> bool;
-}
-
 impl ThunderStorageRequestProcessor {
     pub fn new(state: ThunderState) -> ThunderStorageRequestProcessor {
         ThunderStorageRequestProcessor {
@@ -79,78 +68,96 @@ impl ThunderStorageRequestProcessor {
         }
     }
 
-    #[allow(dead_code)]
-    async fn delete_key(self, namespace: String, key: String) -> bool {
-        let thunder_method = ThunderPlugin::PersistentStorage.method("deleteKey");
-        let client = self.state.clone();
-        let params = Some(DeviceChannelParams::Json(
-            json!({
-                "namespace": namespace,
-                "key": key,
-            })
-            .to_string(),
-        ));
-        let response = client
+    async fn call_storage(
+        state: &ThunderState,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<Value, RippleError> {
+        let thunder_method = ThunderPlugin::PersistentStorage.method(method);
+        let response = state
             .get_thunder_client()
             .call(DeviceCallRequest {
                 method: thunder_method,
-                params: params,
+                params: params.map(|p| DeviceChannelParams::Json(p.to_string())),
             })
             .await;
-        if response.message.get("success").is_none()
-            || response.message["success"].as_bool().unwrap() == false
-        {
-            error!("{}", response.message);
-            return false;
+        match response.message["success"].as_bool() {
+            Some(true) => Ok(response.message),
+            _ => {
+                error!("{}", response.message);
+                Err(RippleError::ProcessorError)
+            }
         }
-        true
     }
 
-    #[allow(dead_code)]
-    async fn delete_namespace(self, namespace: String) -> bool {
-        let thunder_method = ThunderPlugin::PersistentStorage.method("deleteNamespace");
-        let client = self.state.clone();
-        let params = Some(DeviceChannelParams::Json(
-            json!({
-                "namespace": namespace,
-            })
-            .to_string(),
-        ));
-        let response = client
-            .get_thunder_client()
-            .call(DeviceCallRequest {
-                method: thunder_method,
-                params: params,
-            })
-            .await;
-        if response.message.get("success").is_none()
-            || response.message["success"].as_bool().unwrap() == false
-        {
-            error!("{}", response.message);
-            return false;
+    async fn respond_success(
+        state: ThunderState,
+        req: ExtnMessage,
+        result: Result<Value, RippleError>,
+    ) -> bool {
+        match result {
+            Ok(_) => Self::respond(state.get_client(), req, ExtnResponse::Boolean(true))
+                .await
+                .is_ok(),
+            Err(e) => Self::handle_error(state.get_client(), req, e).await,
         }
-        true
     }
 
-    #[allow(dead_code)]
-    async fn flush_cache(self) -> bool {
-        let thunder_method = ThunderPlugin::PersistentStorage.method("flushCache");
-        let client = self.state.clone();
-        let response = client
-            .get_thunder_client()
-            .call(DeviceCallRequest {
-                method: thunder_method,
-                params: None,
-            })
-            .await;
-
-        if response.message.get("success").is_none()
-            || response.message["success"].as_bool().unwrap() == false
-        {
-            error!("{}", response.message);
-            return false;
+    async fn respond_list(
+        state: ThunderState,
+        req: ExtnMessage,
+        result: Result<Value, RippleError>,
+        field: &str,
+    ) -> bool {
+        let list = result.and_then(|message| {
+            serde_json::from_value::<Vec<String>>(message[field].clone())
+                .map_err(|_| RippleError::InvalidOutput)
+        });
+        match list {
+            Ok(list) => Self::respond(state.get_client(), req, ExtnResponse::List(list))
+                .await
+                .is_ok(),
+            Err(e) => Self::handle_error(state.get_client(), req, e).await,
         }
-        true
+    }
+
+    async fn delete_key(
+        state: ThunderState,
+        req: ExtnMessage,
+        data: DeleteStorageProperty,
+    ) -> bool {
+        let params = json!({
+            "namespace": data.namespace,
+            "key": data.key,
+        });
+        let result = Self::call_storage(&state, "deleteKey", Some(params)).await;
+        Self::respond_success(state, req, result).await
+    }
+
+    async fn delete_namespace(state: ThunderState, req: ExtnMessage, namespace: String) -> bool {
+        let params = json!({
+            "namespace": namespace,
+        });
+        let result = Self::call_storage(&state, "deleteNamespace", Some(params)).await;
+        Self::respond_success(state, req, result).await
+    }
+
+    async fn flush_cache(state: ThunderState, req: ExtnMessage) -> bool {
+        let result = Self::call_storage(&state, "flushCache", None).await;
+        Self::respond_success(state, req, result).await
+    }
+
+    async fn get_keys(state: ThunderState, req: ExtnMessage, namespace: String) -> bool {
+        let params = json!({
+            "namespace": namespace,
+        });
+        let result = Self::call_storage(&state, "getKeys", Some(params)).await;
+        Self::respond_list(state, req, result, "keys").await
+    }
+
+    async fn get_namespaces(state: ThunderState, req: ExtnMessage) -> bool {
+        let result = Self::call_storage(&state, "getNamespaces", None).await;
+        Self::respond_list(state, req, result, "namespaces").await
     }
 
     async fn get_value(state: ThunderState, req: ExtnMessage, data: GetStorageProperty) -> bool {
@@ -280,6 +287,19 @@ impl ExtnRequestProcessor for ThunderStorageRequestProcessor {
             DevicePersistenceRequest::Set(set_params) => {
                 Self::set_value(state.clone(), msg, set_params).await
             }
+            DevicePersistenceRequest::Delete(delete_params) => {
+                Self::delete_key(state.clone(), msg, delete_params).await
+            }
+            DevicePersistenceRequest::DeleteNamespace(namespace) => {
+                Self::delete_namespace(state.clone(), msg, namespace).await
+            }
+            DevicePersistenceRequest::GetKeys(namespace) => {
+                Self::get_keys(state.clone(), msg, namespace).await
+            }
+            DevicePersistenceRequest::GetNamespaces => {
+                Self::get_namespaces(state.clone(), msg).await
+            }
+            DevicePersistenceRequest::Flush => Self::flush_cache(state.clone(), msg).await,
         }
     }
 }
@@ -123,16 +123,20 @@ impl ThunderWindowManagerRequestProcessor {
             WindowManagerRequest::MoveToBack(..) => ThunderPlugin::RDKShell.method("moveToBack"),
             WindowManagerRequest::Focus(..) => ThunderPlugin::RDKShell.method("setFocus"),
             WindowManagerRequest::Dimensions(..) => ThunderPlugin::RDKShell.method("setBounds"),
+            WindowManagerRequest::GetClients => ThunderPlugin::RDKShell.method("getClients"),
         }
     }
 
-    fn get_thunder_params(req: &WindowManagerRequest) -> DeviceChannelParams {
+    fn get_thunder_params(req: &WindowManagerRequest) -> Option<DeviceChannelParams> {
+        if let WindowManagerRequest::GetClients = req {
+            return None;
+        }
         let window_name = req.window_name();
         let request_header = WindowManagerRequestHeader {
             callsign: window_name.clone(),
             client: window_name.clone(),
         };
-        match req {
+        Some(match req {
             WindowManagerRequest::Visibility(_, visible) => {
                 ThunderVisibilityRequestParams::new(request_header, visible.clone()).into()
             }
@@ -140,7 +144,7 @@ impl ThunderWindowManagerRequestProcessor {
                 ThunderDimensionsRequestParams::new(request_header, dimensions.clone()).into()
             }
             _ => DeviceChannelParams::Json(serde_json::to_string(&request_header).unwrap()),
-        }
+        })
     }
 }
 
@@ -174,12 +178,30 @@ impl ExtnRequestProcessor for ThunderWindowManagerRequestProcessor {
     ) -> bool {
         let device_request = DeviceCallRequest {
             method: Self::get_thunder_method(&extracted_message),
-            params: Some(Self::get_thunder_params(&extracted_message)),
+            params: Self::get_thunder_params(&extracted_message),
         };
 
         let response = state.get_thunder_client().call(device_request).await;
         if let Some(status) = response.message["success"].as_bool() {
             if status {
+                if let WindowManagerRequest::GetClients = extracted_message {
+                    let clients = response.message["clients"]
+                        .as_array()
+                        .map(|clients| {
+                            clients
+                                .iter()
+                                .filter_map(|c| c.as_str().map(|c| c.to_lowercase()))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    return Self::respond(
+                        state.get_client(),
+                        msg.clone(),
+                        ExtnResponse::List(clients),
+                    )
+                    .await
+                    .is_ok();
+                }
                 return Self::respond(state.get_client(), msg.clone(), ExtnResponse::None(()))
                     .await
                     .is_ok();
@@ -129,12 +129,9 @@ impl ThunderState {
                     if let Some(id) = request.sub_id {
                         let value = request.message.clone();
                         if let Some(handler) = state_c.event_processor.get_handler(&id) {
-                            handler.process(
-                                state_c.clone(),
-                                &id,
-                                value,
-                                handler.callback_type.clone(),
-                            )
+                            for callback_type in state_c.event_processor.get_callback_types(&id) {
+                                handler.process(state_c.clone(), &id, value.clone(), callback_type)
+                            }
                         }
                     }
                 }
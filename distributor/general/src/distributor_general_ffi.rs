@@ -39,6 +39,7 @@ use ripple_sdk::{
 use crate::{
     general_advertising_processor::DistributorAdvertisingProcessor,
     general_discovery_processor::DistributorDiscoveryProcessor,
+    general_fallback_provider_processor::DistributorFallbackProviderProcessor,
     general_media_events_processor::DistributorMediaEventProcessor,
     general_metrics_processor::DistributorMetricsProcessor,
     general_permission_processor::DistributorPermissionProcessor,
@@ -64,6 +65,7 @@ fn init_library() -> CExtnMetadata {
             RippleContract::SessionToken,
             RippleContract::Discovery,
             RippleContract::MediaEvents,
+            RippleContract::FallbackProvider,
         ]),
         Version::new(1, 1, 0),
     );
@@ -96,13 +98,24 @@ fn start_launcher(sender: ExtnSender, receiver: CReceiver<CExtnMessage>) {
                         client.clone(),
                         value.clone(),
                     ));
+                    client.add_request_processor(DistributorSecureStorageProcessor::new(
+                        client.clone(),
+                        value.clone(),
+                    ));
+                    client.add_request_processor(DistributorMetricsProcessor::new(
+                        client.clone(),
+                        value.clone(),
+                    ));
+                    // Denies everything that isn't scripted in fallback_provider.json
+                    client.add_request_processor(DistributorFallbackProviderProcessor::new(
+                        client.clone(),
+                        value.clone(),
+                    ));
                 }
             }
 
             client.add_request_processor(DistributorPermissionProcessor::new(client.clone()));
-            client.add_request_processor(DistributorSecureStorageProcessor::new(client.clone()));
             client.add_request_processor(DistributorAdvertisingProcessor::new(client.clone()));
-            client.add_request_processor(DistributorMetricsProcessor::new(client.clone()));
             client.add_request_processor(DistributorTokenProcessor::new(client.clone()));
             client.add_request_processor(DistributorDiscoveryProcessor::new(client.clone()));
             client.add_request_processor(DistributorMediaEventProcessor::new(client.clone()));
@@ -0,0 +1,172 @@
+// Copyright 2023 Comcast Cable Communications Management, LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+// SPDX-License-Identifier: Apache-2.0
+//
+use std::{collections::HashMap, fs};
+
+use ripple_sdk::{
+    api::firebolt::{
+        fb_keyboard::KeyboardSessionResponse,
+        fb_pin::{PinChallengeResponse, PinChallengeResultReason},
+        provider::{FallbackProviderRequest, ProviderRequestPayload},
+    },
+    async_trait::async_trait,
+    extn::{
+        client::{
+            extn_client::ExtnClient,
+            extn_processor::{
+                DefaultExtnStreamer, ExtnRequestProcessor, ExtnStreamProcessor, ExtnStreamer,
+            },
+        },
+        extn_client_message::{ExtnMessage, ExtnResponse},
+    },
+    log::{debug, error},
+    tokio::sync::mpsc::{Receiver, Sender},
+};
+
+/// Answers provider requests when no app is registered as provider, so grant flows can run
+/// on headless devices and in CI. Answers are scripted per capability in
+/// `fallback_provider.json` under the saved dir, e.g.
+/// `{"xrn:firebolt:capability:usergrant:acknowledgechallenge": {"Boolean": true}}`,
+/// capabilities without a scripted answer are denied or cancelled.
+pub struct DistributorFallbackProviderProcessor {
+    state: FallbackProviderState,
+    streamer: DefaultExtnStreamer,
+}
+
+#[derive(Debug, Clone)]
+pub struct FallbackProviderState {
+    client: ExtnClient,
+    answers: HashMap<String, ExtnResponse>,
+}
+
+impl DistributorFallbackProviderProcessor {
+    pub fn new(client: ExtnClient, saved_dir: String) -> DistributorFallbackProviderProcessor {
+        let path = format!("{}fallback_provider.json", saved_dir);
+        let answers = match fs::read_to_string(&path) {
+            Ok(script) => serde_json::from_str(&script).unwrap_or_else(|e| {
+                error!("Invalid fallback provider script {}: {:?}", path, e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        DistributorFallbackProviderProcessor {
+            state: FallbackProviderState { client, answers },
+            streamer: DefaultExtnStreamer::new(),
+        }
+    }
+
+    /// Answer given when nothing is scripted for the capability, granting always has to be
+    /// scripted explicitly.
+    fn default_answer(request: &FallbackProviderRequest) -> ExtnResponse {
+        match &request.parameters {
+            ProviderRequestPayload::PinChallenge(_) => {
+                ExtnResponse::PinChallenge(PinChallengeResponse {
+                    granted: false,
+                    reason: PinChallengeResultReason::Cancelled,
+                })
+            }
+            ProviderRequestPayload::KeyboardSession(_) => {
+                ExtnResponse::Keyboard(KeyboardSessionResponse {
+                    text: String::default(),
+                    canceled: true,
+                })
+            }
+            _ => ExtnResponse::Boolean(false),
+        }
+    }
+}
+
+impl ExtnStreamProcessor for DistributorFallbackProviderProcessor {
+    type STATE = FallbackProviderState;
+    type VALUE = FallbackProviderRequest;
+
+    fn get_state(&self) -> Self::STATE {
+        self.state.clone()
+    }
+
+    fn receiver(&mut self) -> Receiver<ExtnMessage> {
+        self.streamer.receiver()
+    }
+
+    fn sender(&self) -> Sender<ExtnMessage> {
+        self.streamer.sender()
+    }
+}
+
+#[async_trait]
+impl ExtnRequestProcessor for DistributorFallbackProviderProcessor {
+    fn get_client(&self) -> ExtnClient {
+        self.state.client.clone()
+    }
+
+    async fn process_request(
+        state: Self::STATE,
+        msg: ExtnMessage,
+        extracted_message: Self::VALUE,
+    ) -> bool {
+        let response = state
+            .answers
+            .get(&extracted_message.capability)
+            .cloned()
+            .unwrap_or_else(|| Self::default_answer(&extracted_message));
+        debug!(
+            "Fallback provider answering {} with {:?}",
+            extracted_message.capability, response
+        );
+        Self::respond(state.client.clone(), msg, response)
+            .await
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ripple_sdk::api::firebolt::{
+        fb_pin::{PinChallengeRequest, PinSpace},
+        provider::ChallengeRequestor,
+    };
+
+    use super::*;
+
+    fn request(parameters: ProviderRequestPayload) -> FallbackProviderRequest {
+        FallbackProviderRequest {
+            capability: "xrn:firebolt:capability:usergrant:pinchallenge".into(),
+            method: "challenge".into(),
+            parameters,
+        }
+    }
+
+    #[test]
+    fn test_unscripted_answers_deny() {
+        let pin = request(ProviderRequestPayload::PinChallenge(PinChallengeRequest {
+            pin_space: PinSpace::Purchase,
+            requestor: ChallengeRequestor {
+                id: "app".into(),
+                name: "App".into(),
+            },
+            capability: None,
+        }));
+        assert!(matches!(
+            DistributorFallbackProviderProcessor::default_answer(&pin),
+            ExtnResponse::PinChallenge(PinChallengeResponse { granted: false, .. })
+        ));
+        let generic = request(ProviderRequestPayload::Generic(String::default()));
+        assert!(matches!(
+            DistributorFallbackProviderProcessor::default_answer(&generic),
+            ExtnResponse::Boolean(false)
+        ));
+    }
+}
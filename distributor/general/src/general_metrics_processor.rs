@@ -14,52 +14,231 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 //
+use std::{
+    collections::VecDeque,
+    fs::{self, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use ripple_sdk::{
-    api::firebolt::fb_metrics::BehavioralMetricRequest,
+    api::firebolt::fb_metrics::{BehavioralMetricRequest, MetricsContext},
     async_trait::async_trait,
-    extn::client::{
-        extn_client::ExtnClient,
-        extn_processor::{
-            DefaultExtnStreamer, ExtnRequestProcessor, ExtnStreamProcessor, ExtnStreamer,
+    extn::{
+        client::{
+            extn_client::ExtnClient,
+            extn_processor::{
+                DefaultExtnStreamer, ExtnRequestProcessor, ExtnStreamProcessor, ExtnStreamer,
+            },
         },
+        extn_client_message::{ExtnMessage, ExtnResponse},
+    },
+    log::{error, warn},
+    serde_json,
+    tokio::{
+        self,
+        sync::mpsc::{Receiver, Sender},
     },
-    log::error,
 };
+use serde::{Deserialize, Serialize};
+
+/// Metrics written to the sink at once, a full batch is flushed without waiting for the timer.
+const BATCH_SIZE: usize = 20;
+/// Metrics kept while waiting for a flush, the oldest are dropped beyond it.
+const MAX_PENDING: usize = 200;
+const FLUSH_INTERVAL_SECS: u64 = 30;
+/// Size of the sink file before it's rotated.
+const MAX_FILE_SIZE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MetricRecord {
+    /// Milliseconds since the unix epoch.
+    timestamp: u64,
+    context: Option<MetricsContext>,
+    metric: BehavioralMetricRequest,
+}
+
+#[derive(Debug, Default)]
+struct MetricsBuffer {
+    context: Option<MetricsContext>,
+    pending: VecDeque<MetricRecord>,
+}
 
+/// Local metrics pipeline batching the metrics received from Main and writing them, with the
+/// device context, to a newline delimited json file under the saved dir. Once the file grows
+/// beyond its size it is rotated, keeping a single previous file. Pending metrics are spooled
+/// to a file of their own so they are written by the next run when Ripple stops before a
+/// flush.
 pub struct DistributorMetricsProcessor {
-    client: ExtnClient,
+    state: MetricsState,
     streamer: DefaultExtnStreamer,
 }
 
+#[derive(Debug, Clone)]
+pub struct MetricsState {
+    client: ExtnClient,
+    buffer: Arc<Mutex<MetricsBuffer>>,
+    path: String,
+}
+
+impl MetricsState {
+    fn new(client: ExtnClient, saved_dir: String) -> MetricsState {
+        let state = MetricsState {
+            client,
+            buffer: Arc::new(Mutex::new(MetricsBuffer::default())),
+            path: format!("{}metrics.log", saved_dir),
+        };
+        state.recover();
+        state
+    }
+
+    fn rotated_path(&self) -> String {
+        format!("{}.1", self.path)
+    }
+
+    fn spool_path(&self) -> String {
+        format!("{}.pending", self.path)
+    }
+
+    /// Queues the metrics spooled by the previous run.
+    fn recover(&self) {
+        let file = match fs::File::open(self.spool_path()) {
+            Ok(file) => file,
+            Err(_) => return,
+        };
+        let mut buffer = self.buffer.lock().unwrap();
+        for line in BufReader::new(file).lines().flatten() {
+            match serde_json::from_str(&line) {
+                Ok(record) => buffer.pending.push_back(record),
+                Err(e) => warn!("Dropping unreadable spooled metric {:?}", e),
+            }
+        }
+        while buffer.pending.len() > MAX_PENDING {
+            buffer.pending.pop_front();
+        }
+    }
+
+    fn update_context(&self, context: MetricsContext) {
+        self.buffer.lock().unwrap().context = Some(context);
+    }
+
+    /// Queues the metric and flushes when a full batch is pending. The spool always holds
+    /// the pending metrics, it's rewritten when the oldest one is dropped.
+    fn add(&self, metric: BehavioralMetricRequest) {
+        let full = {
+            let mut buffer = self.buffer.lock().unwrap();
+            let record = MetricRecord {
+                timestamp: now_millis(),
+                context: buffer.context.clone(),
+                metric,
+            };
+            if buffer.pending.len() >= MAX_PENDING {
+                warn!("Metrics buffer full, dropping the oldest metric");
+                buffer.pending.pop_front();
+                buffer.pending.push_back(record);
+                let lines: String = buffer.pending.iter().filter_map(to_line).collect();
+                if let Err(e) = fs::write(self.spool_path(), lines) {
+                    error!("Unable to rewrite the metrics spool {:?}", e);
+                }
+            } else {
+                if let Some(line) = to_line(&record) {
+                    self.append(&self.spool_path(), &line);
+                }
+                buffer.pending.push_back(record);
+            }
+            buffer.pending.len() >= BATCH_SIZE
+        };
+        if full {
+            self.flush();
+        }
+    }
+
+    /// Writes the pending metrics to the sink, they stay pending when the write fails.
+    fn flush(&self) {
+        // the buffer stays locked until the spool is emptied so no metric added meanwhile
+        // is removed with it
+        let mut buffer = self.buffer.lock().unwrap();
+        if buffer.pending.is_empty() {
+            return;
+        }
+        let lines: String = buffer.pending.iter().filter_map(to_line).collect();
+        if let Ok(metadata) = fs::metadata(&self.path) {
+            if metadata.len() + lines.len() as u64 >= MAX_FILE_SIZE_BYTES {
+                if let Err(e) = fs::rename(&self.path, self.rotated_path()) {
+                    error!("Unable to rotate metrics log {:?}", e);
+                }
+            }
+        }
+        if self.append(&self.path, &lines) {
+            buffer.pending.clear();
+            let _ = fs::remove_file(self.spool_path());
+        }
+    }
+
+    fn append(&self, path: &str, lines: &str) -> bool {
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(lines.as_bytes()));
+        if let Err(e) = result {
+            error!("Unable to write metrics to {} {:?}", path, e);
+            return false;
+        }
+        true
+    }
+}
+
+fn to_line(record: &MetricRecord) -> Option<String> {
+    match serde_json::to_string(record) {
+        Ok(line) => Some(format!("{}\n", line)),
+        Err(e) => {
+            error!("Unable to serialize metric {:?}", e);
+            None
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
 impl DistributorMetricsProcessor {
-    pub fn new(client: ExtnClient) -> DistributorMetricsProcessor {
+    pub fn new(client: ExtnClient, saved_dir: String) -> DistributorMetricsProcessor {
+        let state = MetricsState::new(client, saved_dir);
+        let state_c = state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(FLUSH_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                let state = state_c.clone();
+                let _ = tokio::task::spawn_blocking(move || state.flush()).await;
+            }
+        });
         DistributorMetricsProcessor {
-            client,
+            state,
             streamer: DefaultExtnStreamer::new(),
         }
     }
 }
 
 impl ExtnStreamProcessor for DistributorMetricsProcessor {
-    type STATE = ExtnClient;
+    type STATE = MetricsState;
     type VALUE = BehavioralMetricRequest;
 
     fn get_state(&self) -> Self::STATE {
-        self.client.clone()
+        self.state.clone()
     }
 
-    fn receiver(
-        &mut self,
-    ) -> ripple_sdk::tokio::sync::mpsc::Receiver<ripple_sdk::extn::extn_client_message::ExtnMessage>
-    {
+    fn receiver(&mut self) -> Receiver<ExtnMessage> {
         self.streamer.receiver()
     }
 
-    fn sender(
-        &self,
-    ) -> ripple_sdk::tokio::sync::mpsc::Sender<ripple_sdk::extn::extn_client_message::ExtnMessage>
-    {
+    fn sender(&self) -> Sender<ExtnMessage> {
         self.streamer.sender()
     }
 }
@@ -67,114 +246,138 @@ impl ExtnStreamProcessor for DistributorMetricsProcessor {
 #[async_trait]
 impl ExtnRequestProcessor for DistributorMetricsProcessor {
     fn get_client(&self) -> ExtnClient {
-        self.client.clone()
+        self.state.client.clone()
     }
+
     async fn process_request(
         state: Self::STATE,
-        msg: ripple_sdk::extn::extn_client_message::ExtnMessage,
+        msg: ExtnMessage,
         extracted_message: Self::VALUE,
     ) -> bool {
-        match extracted_message {
-            BehavioralMetricRequest::Ready(_) => {
-                if let Err(e) = state
-                    .clone()
-                    .respond(
-                        msg,
-                        ripple_sdk::extn::extn_client_message::ExtnResponse::Boolean(false),
-                    )
-                    .await
-                {
-                    error!("Error sending back response {:?}", e);
-                    return false;
-                }
-                true
-            }
-            BehavioralMetricRequest::SignIn(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::SignOut(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::StartContent(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::StopContent(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::Page(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::Action(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::Error(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::MediaLoadStart(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::MediaPlay(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::MediaPlaying(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::MediaPause(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::MediaWaiting(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::MediaProgress(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::MediaSeeking(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::MediaSeeked(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::MediaRateChanged(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::MediaRenditionChanged(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::MediaEnded(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::TelemetrySignIn(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::TelemetrySignOut(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::TelemetryInternalInitialize(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
-            BehavioralMetricRequest::Raw(_) => {
-                mock_metrics_response(state, msg, extracted_message).await
-            }
+        let state_c = state.clone();
+        // adding a metric writes the spool and may flush, keep the files off the runtime
+        let _ = tokio::task::spawn_blocking(move || match extracted_message {
+            BehavioralMetricRequest::UpdateContext(context) => state_c.update_context(context),
+            metric => state_c.add(metric),
+        })
+        .await;
+        if let Err(e) = state
+            .client
+            .clone()
+            .respond(msg, ExtnResponse::Boolean(true))
+            .await
+        {
+            error!("Error sending back response {:?}", e);
+            return false;
         }
+        true
     }
 }
 
-/// listener for events any events.
-pub async fn mock_metrics_response(
-    state: ExtnClient,
-    msg: ripple_sdk::extn::extn_client_message::ExtnMessage,
-    _extracted_message: BehavioralMetricRequest,
-) -> bool {
-    if let Err(e) = state
-        .clone()
-        .respond(
-            msg,
-            ripple_sdk::extn::extn_client_message::ExtnResponse::Boolean(false),
-        )
-        .await
-    {
-        error!("Error sending back response {:?}", e);
-        return false;
-    }
-    true
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use ripple_sdk::{
+        api::firebolt::fb_metrics::{BehavioralMetricContext, Ready},
+        crossbeam::channel::unbounded,
+        extn::{client::extn_sender::ExtnSender, extn_id::ExtnId},
+    };
+
+    use super::*;
+
+    fn saved_dir(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("metrics_{}_{}/", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.to_str().unwrap().to_owned()
+    }
+
+    fn state(saved_dir: &str) -> MetricsState {
+        let (tx, rx) = unbounded();
+        let sender = ExtnSender::new(
+            tx,
+            ExtnId::get_main_target("test".into()),
+            Vec::new(),
+            Vec::new(),
+        );
+        MetricsState::new(ExtnClient::new(rx, sender), saved_dir.to_owned())
+    }
+
+    fn metric() -> BehavioralMetricRequest {
+        BehavioralMetricRequest::Ready(Ready {
+            context: BehavioralMetricContext {
+                app_id: "app".into(),
+                app_version: "1.0".into(),
+                partner_id: "partner".into(),
+                data_tags: HashSet::new(),
+            },
+            ttmu_ms: 10,
+        })
+    }
+
+    fn line_count(path: &str) -> usize {
+        fs::read_to_string(path)
+            .map(|s| s.lines().count())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_batching() {
+        let state = state(&saved_dir("batching"));
+        for _ in 0..BATCH_SIZE - 1 {
+            state.add(metric());
+        }
+        assert_eq!(line_count(&state.path), 0);
+        assert_eq!(line_count(&state.spool_path()), BATCH_SIZE - 1);
+
+        state.add(metric());
+        assert_eq!(line_count(&state.path), BATCH_SIZE);
+        assert!(fs::metadata(state.spool_path()).is_err());
+    }
+
+    #[test]
+    fn test_rotation() {
+        let state = state(&saved_dir("rotation"));
+        fs::write(&state.path, "x".repeat(MAX_FILE_SIZE_BYTES as usize)).unwrap();
+        state.add(metric());
+        state.flush();
+
+        assert_eq!(
+            fs::metadata(state.rotated_path()).unwrap().len(),
+            MAX_FILE_SIZE_BYTES
+        );
+        assert_eq!(line_count(&state.path), 1);
+    }
+
+    #[test]
+    fn test_failed_flush_keeps_pending() {
+        let state = state(&saved_dir("failed_flush"));
+        // a directory in place of the log makes every append fail
+        fs::create_dir_all(&state.path).unwrap();
+        for _ in 0..MAX_PENDING + BATCH_SIZE {
+            state.add(metric());
+        }
+        assert_eq!(state.buffer.lock().unwrap().pending.len(), MAX_PENDING);
+        assert_eq!(line_count(&state.spool_path()), MAX_PENDING);
+
+        fs::remove_dir(&state.path).unwrap();
+        state.flush();
+        assert_eq!(line_count(&state.path), MAX_PENDING);
+        assert!(state.buffer.lock().unwrap().pending.is_empty());
+        assert!(fs::metadata(state.spool_path()).is_err());
+    }
+
+    #[test]
+    fn test_pending_survive_restart() {
+        let dir = saved_dir("restart");
+        let stopped = state(&dir);
+        stopped.add(metric());
+        stopped.add(metric());
+        drop(stopped);
+
+        let restarted = state(&dir);
+        restarted.flush();
+        assert_eq!(line_count(&restarted.path), 2);
+    }
 }
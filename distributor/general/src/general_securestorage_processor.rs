@@ -14,10 +14,17 @@
 //
 // SPDX-License-Identifier: Apache-2.0
 //
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use ripple_sdk::{
     api::firebolt::fb_secure_storage::{
-        SecureStorageGetResponse, SecureStorageRequest, SecureStorageResponse,
+        SecureStorageClearRequest, SecureStorageGetRequest, SecureStorageGetResponse,
+        SecureStorageRemoveRequest, SecureStorageRequest, SecureStorageResponse,
+        SecureStorageSetRequest, StorageScope,
     },
     async_trait::async_trait,
     extn::{
@@ -27,44 +34,232 @@ use ripple_sdk::{
                 DefaultExtnStreamer, ExtnRequestProcessor, ExtnStreamProcessor, ExtnStreamer,
             },
         },
-        extn_client_message::{ExtnPayload, ExtnPayloadProvider, ExtnResponse},
+        extn_client_message::{ExtnMessage, ExtnPayload, ExtnPayloadProvider, ExtnResponse},
+    },
+    framework::{
+        file_store::FileStore,
+        store_cipher::{FileKeyProvider, StoreCipher},
     },
-    log::error,
+    log::{error, warn},
+    tokio::sync::mpsc::{Receiver, Sender},
+    utils::error::RippleError,
 };
+use serde::{Deserialize, Serialize};
+
+/// Keys an app can keep in one scope.
+const MAX_KEYS: usize = 100;
+/// Bytes of keys and values an app can keep in one scope.
+const MAX_SIZE_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SecureStorageEntry {
+    value: String,
+    /// Seconds since the unix epoch.
+    expires_at: Option<u64>,
+}
+
+impl SecureStorageEntry {
+    fn has_expired(&self, now: u64) -> bool {
+        matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+}
+
+type Namespace = HashMap<String, SecureStorageEntry>;
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct AppSecureStorage {
+    device: Namespace,
+    /// Namespaces of the account scope by account id.
+    accounts: HashMap<String, Namespace>,
+}
+
+/// Local secure storage keeping a namespace per app for the device scope and per app and
+/// account for the account scope. The store is encrypted with a key provisioned in the saved
+/// dir, values are only kept in memory when the key isn't available. An existing store which
+/// can't be decrypted is left as is and writes are refused.
 pub struct DistributorSecureStorageProcessor {
-    client: ExtnClient,
+    state: SecureStorageState,
     streamer: DefaultExtnStreamer,
 }
 
+#[derive(Debug, Clone)]
+pub struct SecureStorageState {
+    client: ExtnClient,
+    store: Arc<RwLock<FileStore<HashMap<String, AppSecureStorage>>>>,
+    writable: bool,
+}
+
+impl SecureStorageState {
+    fn new(client: ExtnClient, saved_dir: String) -> SecureStorageState {
+        let path = format!("{}secure_storage", saved_dir);
+        let key_provider = FileKeyProvider::new(format!("{}secure_storage_key", saved_dir));
+        let cipher = StoreCipher::new(&key_provider).map(Some);
+        let store = FileStore::open(path, HashMap::new(), cipher);
+        // values of a read only store are lost on restart, that's only acceptable when
+        // there was nothing stored before
+        let writable = !store.is_read_only() || !store.exists();
+        if !writable {
+            error!("Secure storage can't be read, refusing writes until it can");
+        }
+        SecureStorageState {
+            client,
+            store: Arc::new(RwLock::new(store)),
+            writable,
+        }
+    }
+
+    fn check_writable(&self) -> Result<(), RippleError> {
+        if self.writable {
+            Ok(())
+        } else {
+            Err(RippleError::ProcessorError)
+        }
+    }
+
+    fn get_namespace<'a>(
+        storage: &'a mut AppSecureStorage,
+        scope: &StorageScope,
+        account_id: &str,
+    ) -> &'a mut Namespace {
+        match scope {
+            StorageScope::Device => &mut storage.device,
+            StorageScope::Account => storage.accounts.entry(account_id.to_owned()).or_default(),
+        }
+    }
+
+    fn get(&self, request: SecureStorageGetRequest) -> Option<String> {
+        let store = self.store.read().unwrap();
+        let storage = store.value.get(&request.app_id)?;
+        let namespace = match request.scope {
+            StorageScope::Device => &storage.device,
+            StorageScope::Account => storage
+                .accounts
+                .get(&request.distributor_session.account_id)?,
+        };
+        namespace
+            .get(&request.key)
+            .filter(|entry| !entry.has_expired(now_secs()))
+            .map(|entry| entry.value.clone())
+    }
+
+    fn set(&self, request: SecureStorageSetRequest) -> Result<(), RippleError> {
+        self.check_writable()?;
+        let now = now_secs();
+        let mut store = self.store.write().unwrap();
+        let storage = store.value.entry(request.app_id.clone()).or_default();
+        let namespace = Self::get_namespace(
+            storage,
+            &request.scope,
+            &request.distributor_session.account_id,
+        );
+        namespace.retain(|_, entry| !entry.has_expired(now));
+
+        let size: usize = namespace
+            .iter()
+            .filter(|(key, _)| **key != request.key)
+            .map(|(key, entry)| key.len() + entry.value.len())
+            .sum();
+        let keys = namespace.len() + usize::from(!namespace.contains_key(&request.key));
+        if keys > MAX_KEYS || size + request.key.len() + request.value.len() > MAX_SIZE_BYTES {
+            warn!(
+                "Secure storage limit reached for {} {:?}",
+                request.app_id, request.scope
+            );
+            return Err(RippleError::InvalidInput);
+        }
+
+        let expires_at = match request.options {
+            Some(options) if options.ttl > 0 => Some(now + options.ttl as u64),
+            _ => None,
+        };
+        namespace.insert(
+            request.key,
+            SecureStorageEntry {
+                value: request.value,
+                expires_at,
+            },
+        );
+        store.sync();
+        Ok(())
+    }
+
+    fn remove(&self, request: SecureStorageRemoveRequest) -> Result<(), RippleError> {
+        self.check_writable()?;
+        let mut store = self.store.write().unwrap();
+        if let Some(storage) = store.value.get_mut(&request.app_id) {
+            let namespace = Self::get_namespace(
+                storage,
+                &request.scope,
+                &request.distributor_session.account_id,
+            );
+            if namespace.remove(&request.key).is_some() {
+                store.sync();
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&self, request: SecureStorageClearRequest) -> Result<(), RippleError> {
+        self.check_writable()?;
+        let mut store = self.store.write().unwrap();
+        for (app_id, storage) in store.value.iter_mut() {
+            if request.app_id.is_some() && request.app_id.as_ref() != Some(app_id) {
+                continue;
+            }
+            match request.scope {
+                Some(StorageScope::Device) => storage.device.clear(),
+                Some(StorageScope::Account) => {
+                    storage
+                        .accounts
+                        .remove(&request.distributor_session.account_id);
+                }
+                None => *storage = AppSecureStorage::default(),
+            }
+        }
+        store
+            .value
+            .retain(|_, storage| !storage.device.is_empty() || !storage.accounts.is_empty());
+        store.sync();
+        Ok(())
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
 impl DistributorSecureStorageProcessor {
-    pub fn new(client: ExtnClient) -> DistributorSecureStorageProcessor {
+    pub fn new(client: ExtnClient, saved_dir: String) -> DistributorSecureStorageProcessor {
         DistributorSecureStorageProcessor {
-            client,
+            state: SecureStorageState::new(client, saved_dir),
             streamer: DefaultExtnStreamer::new(),
         }
     }
+
+    fn to_response(result: Result<(), RippleError>) -> ExtnResponse {
+        match result {
+            Ok(_) => ExtnResponse::None(()),
+            Err(e) => ExtnResponse::Error(e),
+        }
+    }
 }
 
 impl ExtnStreamProcessor for DistributorSecureStorageProcessor {
-    type STATE = ExtnClient;
+    type STATE = SecureStorageState;
     type VALUE = SecureStorageRequest;
 
     fn get_state(&self) -> Self::STATE {
-        self.client.clone()
+        self.state.clone()
     }
 
-    fn receiver(
-        &mut self,
-    ) -> ripple_sdk::tokio::sync::mpsc::Receiver<ripple_sdk::extn::extn_client_message::ExtnMessage>
-    {
+    fn receiver(&mut self) -> Receiver<ExtnMessage> {
         self.streamer.receiver()
     }
 
-    fn sender(
-        &self,
-    ) -> ripple_sdk::tokio::sync::mpsc::Sender<ripple_sdk::extn::extn_client_message::ExtnMessage>
-    {
+    fn sender(&self) -> Sender<ExtnMessage> {
         self.streamer.sender()
     }
 }
@@ -72,69 +267,203 @@ impl ExtnStreamProcessor for DistributorSecureStorageProcessor {
 #[async_trait]
 impl ExtnRequestProcessor for DistributorSecureStorageProcessor {
     fn get_client(&self) -> ExtnClient {
-        self.client.clone()
+        self.state.client.clone()
     }
+
     async fn process_request(
         state: Self::STATE,
-        msg: ripple_sdk::extn::extn_client_message::ExtnMessage,
+        msg: ExtnMessage,
         extracted_message: Self::VALUE,
     ) -> bool {
-        match extracted_message {
-            SecureStorageRequest::Set(_req) => {
-                if let Err(e) = state
-                    .clone()
-                    .respond(
-                        msg,
-                        ripple_sdk::extn::extn_client_message::ExtnResponse::None(()),
-                    )
-                    .await
-                {
-                    error!("Error sending back response {:?}", e);
-                    return false;
-                }
-                true
-            }
-            SecureStorageRequest::Get(_req) => {
+        let response = match extracted_message {
+            SecureStorageRequest::Get(req) => {
                 let resp = SecureStorageGetResponse {
-                    value: Some(String::from("VGhpcyBub3QgYSByZWFsIHRva2VuLgo=")),
+                    value: state.get(req),
                 };
-
-                if let Err(e) = state
-                    .clone()
-                    .respond(
-                        msg,
-                        if let ExtnPayload::Response(r) =
-                            SecureStorageResponse::Get(resp).get_extn_payload()
-                        {
-                            r
-                        } else {
-                            ExtnResponse::Error(
-                                ripple_sdk::utils::error::RippleError::ProcessorError,
-                            )
-                        },
-                    )
-                    .await
+                if let ExtnPayload::Response(r) =
+                    SecureStorageResponse::Get(resp).get_extn_payload()
                 {
-                    error!("Error sending back response {:?}", e);
-                    return false;
+                    r
+                } else {
+                    ExtnResponse::Error(RippleError::ProcessorError)
                 }
-                true
             }
-
-            SecureStorageRequest::Remove(_req) => {
-                if let Err(e) = state
-                    .clone()
-                    .respond(
-                        msg,
-                        ripple_sdk::extn::extn_client_message::ExtnResponse::None(()),
-                    )
-                    .await
-                {
-                    error!("Error sending back response {:?}", e);
-                    return false;
-                }
-                true
+            SecureStorageRequest::Set(req) | SecureStorageRequest::SetForApp(req) => {
+                Self::to_response(state.set(req))
             }
+            SecureStorageRequest::Remove(req) | SecureStorageRequest::RemoveForApp(req) => {
+                Self::to_response(state.remove(req))
+            }
+            SecureStorageRequest::Clear(req) => Self::to_response(state.clear(req)),
+        };
+        if let Err(e) = state.client.clone().respond(msg, response).await {
+            error!("Error sending back response {:?}", e);
+            return false;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ripple_sdk::{
+        api::{firebolt::fb_secure_storage::StorageSetOptions, session::AccountSession},
+        crossbeam::channel::unbounded,
+        extn::{client::extn_sender::ExtnSender, extn_id::ExtnId},
+    };
+
+    use super::*;
+
+    fn state(name: &str) -> SecureStorageState {
+        let dir =
+            std::env::temp_dir().join(format!("secure_storage_{}_{}/", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        state_in(dir.to_str().unwrap())
+    }
+
+    fn state_in(dir: &str) -> SecureStorageState {
+        let (tx, rx) = unbounded();
+        let sender = ExtnSender::new(
+            tx,
+            ExtnId::get_main_target("test".into()),
+            Vec::new(),
+            Vec::new(),
+        );
+        SecureStorageState::new(ExtnClient::new(rx, sender), dir.to_owned())
+    }
+
+    fn session(account_id: &str) -> AccountSession {
+        AccountSession {
+            id: "id".into(),
+            token: "token".into(),
+            account_id: account_id.into(),
+            device_id: "device".into(),
+        }
+    }
+
+    fn set_request(
+        scope: StorageScope,
+        account_id: &str,
+        key: &str,
+        value: &str,
+    ) -> SecureStorageSetRequest {
+        SecureStorageSetRequest {
+            app_id: "app".into(),
+            scope,
+            key: key.into(),
+            value: value.into(),
+            options: None,
+            distributor_session: session(account_id),
+        }
+    }
+
+    fn get(
+        state: &SecureStorageState,
+        scope: StorageScope,
+        account_id: &str,
+        key: &str,
+    ) -> Option<String> {
+        state.get(SecureStorageGetRequest {
+            app_id: "app".into(),
+            scope,
+            key: key.into(),
+            distributor_session: session(account_id),
+        })
+    }
+
+    #[test]
+    fn test_scope_namespaces() {
+        let state = state("scopes");
+        state
+            .set(set_request(StorageScope::Device, "a", "key", "device"))
+            .unwrap();
+        state
+            .set(set_request(StorageScope::Account, "a", "key", "account a"))
+            .unwrap();
+
+        assert_eq!(
+            get(&state, StorageScope::Device, "b", "key"),
+            Some("device".into())
+        );
+        assert_eq!(
+            get(&state, StorageScope::Account, "a", "key"),
+            Some("account a".into())
+        );
+        assert_eq!(get(&state, StorageScope::Account, "b", "key"), None);
+    }
+
+    #[test]
+    fn test_ttl_expiry() {
+        let state = state("ttl");
+        let mut request = set_request(StorageScope::Device, "a", "key", "value");
+        request.options = Some(StorageSetOptions { ttl: 60 });
+        state.set(request).unwrap();
+        assert_eq!(
+            get(&state, StorageScope::Device, "a", "key"),
+            Some("value".into())
+        );
+
+        state
+            .store
+            .write()
+            .unwrap()
+            .value
+            .get_mut("app")
+            .unwrap()
+            .device
+            .get_mut("key")
+            .unwrap()
+            .expires_at = Some(now_secs() - 1);
+        assert_eq!(get(&state, StorageScope::Device, "a", "key"), None);
+    }
+
+    #[test]
+    fn test_limits() {
+        let state = state("limits");
+        for i in 0..MAX_KEYS {
+            state
+                .set(set_request(StorageScope::Device, "a", &i.to_string(), "v"))
+                .unwrap();
         }
+        assert!(state
+            .set(set_request(StorageScope::Device, "a", "one more", "v"))
+            .is_err());
+        // replacing a key doesn't count as a new one
+        assert!(state
+            .set(set_request(StorageScope::Device, "a", "0", "w"))
+            .is_ok());
+
+        let large = "v".repeat(MAX_SIZE_BYTES);
+        assert!(state
+            .set(set_request(StorageScope::Account, "a", "key", &large))
+            .is_err());
+        assert!(state
+            .set(set_request(
+                StorageScope::Account,
+                "a",
+                "key",
+                &large[..MAX_SIZE_BYTES - 3]
+            ))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_unreadable_store_refuses_writes() {
+        let dir =
+            std::env::temp_dir().join(format!("secure_storage_broken_{}/", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let dir = dir.to_str().unwrap();
+        let path = format!("{}secure_storage", dir);
+        fs::write(&path, "not encrypted").unwrap();
+
+        let state = state_in(dir);
+        assert!(state
+            .set(set_request(StorageScope::Device, "a", "key", "value"))
+            .is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "not encrypted");
     }
 }
@@ -18,6 +18,7 @@
 mod distributor_general_ffi;
 mod general_advertising_processor;
 mod general_discovery_processor;
+mod general_fallback_provider_processor;
 mod general_media_events_processor;
 mod general_metrics_processor;
 mod general_permission_processor;
@@ -68,12 +68,7 @@ fn get_rpc_extns(sender: ExtnSender, receiver: Receiver<CExtnMessage>) -> Method
 }
 
 fn get_extended_capabilities() -> Option<FireboltVersionManifest> {
-    if let Ok(version_manifest) =
-        serde_json::from_str(std::include_str!("./extended-open-rpc.json"))
-    {
-        return Some(version_manifest);
-    }
-    None
+    FireboltVersionManifest::from_fragment(std::include_str!("./extended-open-rpc.json")).ok()
 }
 
 fn init_jsonrpsee_builder() -> JsonRpseeExtnBuilder {